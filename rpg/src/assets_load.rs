@@ -4,15 +4,15 @@ use serde::Deserialize;
 use std::path::{Path, PathBuf};
 
 #[derive(Deserialize)]
-struct Bgm {
-    name: String,
-    path: PathBuf,
+pub(crate) struct Bgm {
+    pub(crate) name: String,
+    pub(crate) path: PathBuf,
     #[serde(rename = "loop")]
     is_loop: bool,
 }
 
 #[derive(Deserialize)]
-struct BgmList(Vec<Bgm>);
+pub(crate) struct BgmList(pub(crate) Vec<Bgm>);
 
 fn load_bgm(engine: &mut AnimationEngine) -> anyhow::Result<()> {
     info!("Start loading bgm...");
@@ -145,6 +145,30 @@ fn load_animation(engine: &mut AnimationEngine) -> anyhow::Result<()> {
         engine.load_animation_yaml(&name, &path)?;
     }
 
+    for path in engine.filesystem().read_dir("/animation/victory/")? {
+        let name = path.to_string_lossy();
+
+        trace!("[load animation] name: {}, path: {:?}", name, path);
+
+        engine.load_animation_yaml(&name, &path)?;
+    }
+
+    for path in engine.filesystem().read_dir("/animation/game-over/")? {
+        let name = path.to_string_lossy();
+
+        trace!("[load animation] name: {}, path: {:?}", name, path);
+
+        engine.load_animation_yaml(&name, &path)?;
+    }
+
+    for path in engine.filesystem().read_dir("/animation/ending/")? {
+        let name = path.to_string_lossy();
+
+        trace!("[load animation] name: {}, path: {:?}", name, path);
+
+        engine.load_animation_yaml(&name, &path)?;
+    }
+
     info!("Finish loading animations!");
 
     Ok(())
@@ -0,0 +1,211 @@
+use rand::prelude::*;
+use std::env;
+use std::fs;
+use std::io;
+
+use rpg::game_data::*;
+use rpg::simulator::*;
+use rpg::{load_original_texts, PlayerState};
+
+const USAGE: &str = "\
+Usage: battle-sim [options]
+
+Options:
+  --resources <dir>      Resource directory (default: ./rpg/resources)
+  --i18n <dir>           Localization directory (default: ./rpg/i18n)
+  --fights <n>           Fights per player/enemy pair (default: 1000)
+  --seed <n>             Seed of the first fight (default: 0)
+  --policy <name>        random | greedy | scripted (default: greedy)
+  --script <file>        YAML list of commands for the scripted policy
+  --player <index>       Simulate only this player
+  --enemy <id>           Simulate only this enemy
+  --companions <ids>     Comma separated enemy ids fighting alongside
+  --skills <ids>         Comma separated skill ids the player owns
+  --items <id:count,..>  Items the player owns";
+
+struct Options {
+    resources: String,
+    i18n: String,
+    fights: u64,
+    seed: u64,
+    policy: String,
+    script: Option<String>,
+    player: Option<usize>,
+    enemy: Option<usize>,
+    companions: Vec<EnemyId>,
+    skills: Vec<SkillId>,
+    items: Vec<(ItemId, u32)>,
+}
+impl Options {
+    fn parse() -> anyhow::Result<Self> {
+        let mut options = Self {
+            resources: "./rpg/resources".into(),
+            i18n: "./rpg/i18n".into(),
+            fights: 1000,
+            seed: 0,
+            policy: "greedy".into(),
+            script: None,
+            player: None,
+            enemy: None,
+            companions: vec![],
+            skills: vec![],
+            items: vec![],
+        };
+
+        let mut args = env::args().skip(1);
+        while let Some(arg) = args.next() {
+            let mut value = || {
+                args.next()
+                    .ok_or_else(|| anyhow::Error::msg(format!("Missing value for {}", arg)))
+            };
+            match arg.as_str() {
+                "--resources" => options.resources = value()?,
+                "--i18n" => options.i18n = value()?,
+                "--fights" => options.fights = value()?.parse()?,
+                "--seed" => options.seed = value()?.parse()?,
+                "--policy" => options.policy = value()?,
+                "--script" => options.script = Some(value()?),
+                "--player" => options.player = Some(value()?.parse()?),
+                "--enemy" => options.enemy = Some(value()?.parse()?),
+                "--companions" => {
+                    for id in value()?.split(',') {
+                        options.companions.push(EnemyId(id.parse()?));
+                    }
+                }
+                "--skills" => {
+                    for id in value()?.split(',') {
+                        options.skills.push(SkillId(id.parse()?));
+                    }
+                }
+                "--items" => {
+                    for item in value()?.split(',') {
+                        let (id, count) = item.split_once(':').unwrap_or((item, "1"));
+                        options.items.push((ItemId(id.parse()?), count.parse()?));
+                    }
+                }
+                "--help" => {
+                    println!("{}", USAGE);
+                    std::process::exit(0);
+                }
+                _ => anyhow::bail!("Unknown option: {}\n{}", arg, USAGE),
+            }
+        }
+        if 1 + options.companions.len() > MAX_ENEMIES {
+            anyhow::bail!(
+                "Too many companions: at most {} enemies can fight at once",
+                MAX_ENEMIES
+            );
+        }
+        Ok(options)
+    }
+
+    fn policy(&self) -> anyhow::Result<Box<dyn BattlePolicy>> {
+        Ok(match self.policy.as_str() {
+            "random" => Box::new(RandomPolicy),
+            "greedy" => Box::new(GreedyPolicy::default()),
+            "scripted" => {
+                let path = self
+                    .script
+                    .as_ref()
+                    .ok_or_else(|| anyhow::Error::msg("Scripted policy needs --script"))?;
+                let reader = io::BufReader::new(fs::File::open(path)?);
+                Box::new(ScriptedPolicy::new(serde_yaml::from_reader(reader)?)?)
+            }
+            policy => anyhow::bail!("Unknown policy: {}", policy),
+        })
+    }
+
+    fn player_state(&self, player_data: &PlayerData) -> PlayerState {
+        let mut player_state = PlayerState::new(player_data);
+        for &skill_id in self.skills.iter() {
+            if player_data.skills.iter().any(|s| s.id == skill_id) {
+                player_state.add_skill(skill_id, &player_data.skills);
+            }
+        }
+        for &(item_id, count) in self.items.iter() {
+            for _ in 0..count {
+                player_state.add_item(item_id);
+            }
+        }
+        player_state
+    }
+}
+
+fn main() -> anyhow::Result<()> {
+    let options = Options::parse()?;
+    let game_data = GameData::load_from_dir(&options.resources)?;
+    let texts = load_original_texts(&options.i18n)?;
+    let item_data = game_data.item_data();
+    let mut policy = options.policy()?;
+
+    let companions = options
+        .companions
+        .iter()
+        .map(|id| {
+            game_data
+                .enemy_data()
+                .iter()
+                .find(|e| e.id == *id)
+                .ok_or_else(|| anyhow::Error::msg(format!("No enemy data. enemy id: {:?}", id)))
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    println!(
+        "{:<8} {:<16} {:>8} {:>8} {:>8} {:>10} {:>10}",
+        "player", "enemy", "win", "escaped", "limit", "avg turns", "avg hp"
+    );
+    for (player_index, player_data) in game_data.player_data().iter().enumerate() {
+        if options.player.is_some_and(|p| p != player_index) {
+            continue;
+        }
+        for enemy_data in game_data.enemy_data().iter() {
+            if options.enemy.is_some_and(|e| e != enemy_data.id.0) {
+                continue;
+            }
+            let enemies = std::iter::once(enemy_data)
+                .chain(companions.iter().copied())
+                .collect::<Vec<_>>();
+
+            let mut wins = 0;
+            let mut escapes = 0;
+            let mut limits = 0;
+            let mut total_turns = 0;
+            let mut total_hp = 0;
+            for i in 0..options.fights {
+                let mut rng = StdRng::seed_from_u64(options.seed + i);
+                let mut player_state = options.player_state(player_data);
+                let battle = simulate_battle(
+                    player_data,
+                    item_data,
+                    &enemies,
+                    &mut player_state,
+                    policy.as_mut(),
+                    &mut rng,
+                );
+                match battle.result {
+                    SimulatedResult::Win => wins += 1,
+                    SimulatedResult::Escaped => escapes += 1,
+                    SimulatedResult::TurnLimit => limits += 1,
+                    SimulatedResult::Lose => (),
+                }
+                total_turns += battle.turns as u64;
+                total_hp += battle.player_hp as i64;
+            }
+
+            let fights = options.fights.max(1) as f64;
+            println!(
+                "{:<8} {:<16} {:>7.1}% {:>7.1}% {:>7.1}% {:>10.2} {:>10.1}",
+                player_index,
+                texts
+                    .get(&enemy_data.enemy_name)
+                    .unwrap_or(&enemy_data.enemy_name),
+                wins as f64 / fights * 100.0,
+                escapes as f64 / fights * 100.0,
+                limits as f64 / fights * 100.0,
+                total_turns as f64 / fights,
+                total_hp as f64 / fights,
+            );
+        }
+    }
+    Ok(())
+}
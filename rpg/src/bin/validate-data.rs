@@ -0,0 +1,72 @@
+use std::env;
+use std::process;
+
+use rpg::game_data::*;
+
+const USAGE: &str = "\
+Usage: validate-data [options]
+
+Options:
+  --resources <dir>  Resource directory (default: ./rpg/resources)
+  --i18n <dir>       Localization directory (default: ./rpg/i18n)
+  --strict           Fail on warnings such as missing translations";
+
+struct Options {
+    resources: String,
+    i18n: String,
+    strict: bool,
+}
+impl Options {
+    fn parse() -> anyhow::Result<Self> {
+        let mut options = Self {
+            resources: "./rpg/resources".into(),
+            i18n: "./rpg/i18n".into(),
+            strict: false,
+        };
+
+        let mut args = env::args().skip(1);
+        while let Some(arg) = args.next() {
+            let mut value = || {
+                args.next()
+                    .ok_or_else(|| anyhow::Error::msg(format!("Missing value for {}", arg)))
+            };
+            match arg.as_str() {
+                "--resources" => options.resources = value()?,
+                "--i18n" => options.i18n = value()?,
+                "--strict" => options.strict = true,
+                "--help" => {
+                    println!("{}", USAGE);
+                    process::exit(0);
+                }
+                _ => anyhow::bail!("Unknown option: {}\n{}", arg, USAGE),
+            }
+        }
+
+        Ok(options)
+    }
+}
+
+fn main() -> anyhow::Result<()> {
+    let options = Options::parse()?;
+    let problems = validate_dir(&options.resources, &options.i18n);
+
+    for problem in problems.iter() {
+        println!("{}", problem);
+    }
+    if problems.is_empty() {
+        println!("No problems found.");
+        return Ok(());
+    }
+
+    let warnings = problems
+        .iter()
+        .filter(|p| p.severity == Severity::Warning)
+        .count();
+    let errors = problems.len() - warnings;
+    println!("{} error(s), {} warning(s) found.", errors, warnings);
+    if errors > 0 || options.strict {
+        process::exit(1);
+    }
+
+    Ok(())
+}
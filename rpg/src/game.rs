@@ -1,11 +1,18 @@
 mod battle;
+mod credits;
+mod ending;
 mod explore;
 mod game;
+mod game_over;
+mod monster_book;
 mod opening;
 mod options;
 mod player_state;
+mod run_setup;
+mod statistics;
 mod title;
 mod user_guide;
 
+pub use battle::simulator;
 pub use game::*;
 pub use player_state::*;
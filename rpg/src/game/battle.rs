@@ -1,12 +1,17 @@
 use animation_engine::*;
 use log::info;
+use rand::prelude::*;
 
 use crate::game;
 use crate::game_data;
 
 mod battle_model;
+mod battle_reward;
 mod battle_scene;
 mod battle_view;
+pub mod simulator;
+mod victory_summary;
+pub use battle_reward::*;
 pub use battle_scene::*;
 
 pub async fn battle(
@@ -14,12 +19,24 @@ pub async fn battle(
     player_index: usize,
     player_data: &game_data::PlayerData,
     item_data: &Vec<game_data::ItemData>,
-    // battle_data: usize,
+    encounter: &Encounter<'_>,
     player_state: &mut game::PlayerState,
-    time: game_data::BattleTime,
+    rng: &mut impl Rng,
 ) -> BattleResult {
     info!("Enter Battle Scene!");
-    BattleScene::new(cx, player_index, player_data, item_data, time)
-        .start(player_state)
-        .await
+    let mut scene = BattleScene::new(
+        cx,
+        player_index,
+        player_data,
+        item_data,
+        encounter,
+        player_state,
+    );
+    let result = scene.start(player_state, rng).await;
+    player_state.record_battle(scene.turn_count());
+    drop(scene);
+    if let BattleResult::Win(rewards) = &result {
+        victory_summary::victory_summary(cx, rewards, item_data).await;
+    }
+    result
 }
@@ -1,65 +1,325 @@
+use log::warn;
+use rand::distributions::*;
+use rand::prelude::*;
+
 use crate::game::battle::battle_view::*;
+use crate::game::battle::BattleRewards;
 use crate::game::PlayerState;
 use crate::game_data::*;
 
 mod battle_state_store;
+mod modifier;
 
 use battle_state_store::*;
+use modifier::*;
 
 pub(super) struct SelectCommandData<'a> {
     pub skills: Vec<SkillWindowItem>,
     pub skill_data: Vec<&'a SkillData>,
     pub items: Vec<ItemWindowItem>,
     pub item_data: Vec<&'a ItemData>,
+    pub player_info: InfoWindowData,
+    pub enemy_info: Vec<InfoWindowData>,
+    pub enemy_alive: Vec<bool>,
 }
 
+/// 第二要素は対象とする敵のインデックス
 pub(super) enum BattleCommand {
-    Skill(SkillId),
-    Item(ItemId),
+    Skill(SkillId, usize),
+    Item(ItemId, usize),
+    Escape,
 }
 
-pub(super) enum BattleViewCommand<'a> {
-    Message { key: String, args: &'a [&'a str] },
+pub(super) enum BattleViewCommand {
+    Message {
+        key: String,
+        args: Vec<String>,
+    },
     PlayerBlink,
-    EnemyBlink,
-    EnemyDamage { damage: i32, hp: i32, max_hp: i32 },
-    EnemyHeal { heal: i32, hp: i32, max_hp: i32 },
-    EnemyDown,
-    PlayerDamage { damage: i32, hp: i32, max_hp: i32 },
-    PlayerHeal { heal: i32, hp: i32, max_hp: i32 },
-    PlayerSetTp { tp: i32, max_tp: i32 },
+    EnemyBlink {
+        index: usize,
+    },
+    EnemyDamage {
+        index: usize,
+        damage: i32,
+        hp: i32,
+        max_hp: i32,
+    },
+    EnemyHeal {
+        index: usize,
+        heal: i32,
+        hp: i32,
+        max_hp: i32,
+    },
+    EnemyDown {
+        index: usize,
+    },
+    EnemyPhaseChange {
+        index: usize,
+    },
+    PlayerDamage {
+        damage: i32,
+        hp: i32,
+        max_hp: i32,
+    },
+    PlayerHeal {
+        heal: i32,
+        hp: i32,
+        max_hp: i32,
+    },
+    PlayerSetTp {
+        tp: i32,
+        max_tp: i32,
+    },
+    /// 直後のダメージが会心の一撃であることを示す
+    Critical,
+    /// 直後のダメージが弱点を突いたことを示す
+    Weak,
+    PlayerMiss,
+    EnemyMiss {
+        index: usize,
+    },
+    PlayerModifiers {
+        items: Vec<ModifierViewItem>,
+    },
+    EnemyModifiers {
+        index: usize,
+        items: Vec<ModifierViewItem>,
+    },
     WaitKey,
-    Delay { millis: u64 },
+    Delay {
+        millis: u64,
+    },
 }
 
+#[derive(PartialEq, Eq, Debug)]
 pub(super) enum BattleTurnResult {
     Win,
     Lose,
+    Escaped,
     Continue,
 }
 
+#[derive(PartialEq, Eq, Clone, Copy)]
+enum Side {
+    Player,
+    Enemy(usize),
+}
+
+const DEFAULT_CRITICAL_RATE: f64 = 0.05;
+const DEFAULT_EVASION_RATE: f64 = 0.05;
+const CRITICAL_MULTIPLIER: f64 = 1.5;
+
+struct BattlerStatus {
+    hp: i32,
+    max_hp: i32,
+    attack: i32,
+    defense: i32,
+    critical_rate: f64,
+    evasion_rate: f64,
+    modifiers: Modifiers,
+}
+impl BattlerStatus {
+    fn new(
+        max_hp: i32,
+        attack: i32,
+        defense: i32,
+        critical_rate: Option<f64>,
+        evasion_rate: Option<f64>,
+    ) -> Self {
+        Self {
+            hp: max_hp,
+            max_hp,
+            attack,
+            defense,
+            critical_rate: critical_rate.unwrap_or(DEFAULT_CRITICAL_RATE),
+            evasion_rate: evasion_rate.unwrap_or(DEFAULT_EVASION_RATE),
+            modifiers: Modifiers::new(),
+        }
+    }
+
+    fn effective_attack(&self) -> i32 {
+        let rate = 100 + self.modifiers.value(ModifierKind::AttackUp)
+            - self.modifiers.value(ModifierKind::AttackDown);
+        self.attack * rate.max(0) / 100
+    }
+
+    fn effective_defense(&self) -> i32 {
+        let rate = 100 + self.modifiers.value(ModifierKind::DefenseUp)
+            - self.modifiers.value(ModifierKind::DefenseDown);
+        self.defense * rate.max(0) / 100
+    }
+
+    fn guard_rate(&self) -> i32 {
+        self.modifiers.value(ModifierKind::Guard).clamp(0, 100)
+    }
+}
+
+fn calc_damage(attack: i32, defense: i32, multiplier: f64) -> i32 {
+    (attack as f64 * multiplier - defense as f64 * 0.5)
+        .round()
+        .max(1.0) as i32
+}
+
+struct EnemyBattler<'a> {
+    data: &'a EnemyData,
+    status: BattlerStatus,
+    phase: Option<usize>,
+    down: bool,
+    battle_state: BattleStateStore<'a>,
+}
+impl<'a> EnemyBattler<'a> {
+    fn new(data: &'a EnemyData) -> Self {
+        Self {
+            data,
+            status: BattlerStatus::new(
+                data.max_hp,
+                data.attack,
+                data.defense,
+                data.critical_rate,
+                data.evasion_rate,
+            ),
+            phase: None,
+            down: false,
+            battle_state: BattleStateStore::new(),
+        }
+    }
+}
+
 pub(super) struct BattleModel<'a> {
-    player_index: usize,
     player_data: &'a PlayerData,
     item_data: &'a Vec<ItemData>,
-    // battle_data
+    player: BattlerStatus,
+    player_tp: i32,
+    player_max_tp: i32,
+    enemies: Vec<EnemyBattler<'a>>,
     battle_state: BattleStateStore<'a>,
+    /// 最後に行動した敵。戦闘不能になった原因として使う
+    last_attacker: usize,
+    get_text: fn(&str) -> String,
 }
 impl<'a> BattleModel<'a> {
     pub(super) fn new(
-        player_index: usize,
         player_data: &'a PlayerData,
         item_data: &'a Vec<ItemData>,
+        enemy_data: &[&'a EnemyData],
+        player_state: &PlayerState,
+        get_text: fn(&str) -> String,
     ) -> Self {
+        let (hp, max_hp) = player_state.get_hp();
+        let (tp, max_tp) = player_state.get_tp();
+        let mut player = BattlerStatus::new(
+            max_hp,
+            player_data.attack,
+            player_data.defense,
+            player_data.critical_rate,
+            player_data.evasion_rate,
+        );
+        player.hp = hp;
+        let enemies = enemy_data.iter().map(|&e| EnemyBattler::new(e)).collect();
         let battle_state = BattleStateStore::new();
         Self {
-            player_index,
             player_data,
             item_data,
+            player,
+            player_tp: tp,
+            player_max_tp: max_tp,
+            enemies,
             battle_state,
+            last_attacker: 0,
+            get_text,
         }
     }
 
+    pub(super) fn enemy_count(&self) -> usize {
+        self.enemies.len()
+    }
+
+    pub(super) fn enemy_data(&self, index: usize) -> &'a EnemyData {
+        self.enemies[index].data
+    }
+
+    pub(super) fn enemy_name(&self, index: usize) -> String {
+        self.battler_name(Side::Enemy(index))
+    }
+
+    pub(super) fn player_hp(&self) -> (i32, i32) {
+        (self.player.hp, self.player.max_hp)
+    }
+
+    pub(super) fn player_tp(&self) -> (i32, i32) {
+        (self.player_tp, self.player_max_tp)
+    }
+
+    pub(super) fn enemy_hp(&self, index: usize) -> (i32, i32) {
+        let status = &self.enemies[index].status;
+        (status.hp, status.max_hp)
+    }
+
+    pub(super) fn killed_by(&self) -> EnemyId {
+        self.enemies[self.last_attacker].data.id
+    }
+
+    pub(super) fn roll_rewards(
+        &self,
+        player_state: &PlayerState,
+        rng: &mut impl Rng,
+    ) -> BattleRewards {
+        let enemy_data = self.enemies.iter().map(|e| e.data).collect::<Vec<_>>();
+        BattleRewards::roll(&enemy_data, self.player_data, player_state, rng)
+    }
+
+    pub(super) fn write_back_player_state(&self, player_state: &mut PlayerState) {
+        player_state.set_hp(self.player.hp);
+        player_state.set_tp(self.player_tp);
+    }
+
+    fn battler(&self, side: Side) -> &BattlerStatus {
+        match side {
+            Side::Player => &self.player,
+            Side::Enemy(index) => &self.enemies[index].status,
+        }
+    }
+
+    fn battler_mut(&mut self, side: Side) -> &mut BattlerStatus {
+        match side {
+            Side::Player => &mut self.player,
+            Side::Enemy(index) => &mut self.enemies[index].status,
+        }
+    }
+
+    fn battler_name(&self, side: Side) -> String {
+        match side {
+            Side::Player => (self.get_text)("battle-player-name"),
+            Side::Enemy(index) => {
+                let id = self.enemies[index].data.id;
+                let name = (self.get_text)(&self.enemies[index].data.enemy_name);
+                if self.enemies.iter().filter(|e| e.data.id == id).count() > 1 {
+                    let n = self.enemies[..index]
+                        .iter()
+                        .filter(|e| e.data.id == id)
+                        .count();
+                    format!("{}{}", name, (b'A' + n as u8) as char)
+                } else {
+                    name
+                }
+            }
+        }
+    }
+
+    fn battle_state_mut(&mut self, side: Side) -> &mut BattleStateStore<'a> {
+        match side {
+            Side::Player => &mut self.battle_state,
+            Side::Enemy(index) => &mut self.enemies[index].battle_state,
+        }
+    }
+
+    fn alive_enemies(&self) -> Vec<usize> {
+        (0..self.enemies.len())
+            .filter(|&index| !self.enemies[index].down)
+            .collect()
+    }
+
     fn get_skill_data(
         &self,
         player_state: &PlayerState,
@@ -79,7 +339,7 @@ impl<'a> BattleModel<'a> {
                 } else {
                     Number::Infinity
                 };
-                let active = skill_data.skill_cost != SkillCost::Infinity;
+                let active = self.can_afford(skill_data.skill_cost);
                 (
                     SkillWindowItem {
                         name_key: skill_data.skill_name.to_owned(),
@@ -92,13 +352,20 @@ impl<'a> BattleModel<'a> {
             .unzip::<_, _, Vec<_>, Vec<_>>()
     }
 
+    fn can_afford(&self, skill_cost: SkillCost) -> bool {
+        match skill_cost {
+            SkillCost::Cost(cost) => cost as i32 <= self.player_tp,
+            SkillCost::Infinity => false,
+        }
+    }
+
     fn get_item_data(&self, player_state: &PlayerState) -> (Vec<ItemWindowItem>, Vec<&ItemData>) {
         player_state
             .get_items()
             .iter()
             .flat_map(|&(item_id, item_count)| {
                 let item_data = self.item_data.iter().find(|&i| i.id == item_id).unwrap();
-                let active = true;
+                let active = item_data.use_on_down != Some(true);
                 (0..item_count).map(move |_| {
                     (
                         ItemWindowItem {
@@ -112,7 +379,7 @@ impl<'a> BattleModel<'a> {
             .unzip::<_, _, Vec<_>, Vec<_>>()
     }
 
-    pub(super) fn select_command_data(&self, player_state: &PlayerState) -> SelectCommandData {
+    pub(super) fn select_command_data(&self, player_state: &PlayerState) -> SelectCommandData<'_> {
         let (skills, skill_data) = self.get_skill_data(player_state);
         let (items, item_data) = self.get_item_data(player_state);
         SelectCommandData {
@@ -120,56 +387,795 @@ impl<'a> BattleModel<'a> {
             skill_data,
             items,
             item_data,
+            player_info: self.player_info(),
+            enemy_info: (0..self.enemies.len())
+                .map(|index| self.enemy_info(index))
+                .collect(),
+            enemy_alive: self.enemies.iter().map(|e| !e.down).collect(),
         }
     }
 
-    pub(super) fn turn_start(&mut self) -> u32 {
-        self.battle_state.add("bi-turn-count", 1);
+    fn player_info(&self) -> InfoWindowData {
+        InfoWindowData {
+            title_key: self.player_data.opening_legendary_name.to_owned(),
+            lines: vec![
+                (
+                    "battle-info-hp".into(),
+                    vec![self.player.hp.to_string(), self.player.max_hp.to_string()],
+                ),
+                (
+                    "battle-info-tp".into(),
+                    vec![self.player_tp.to_string(), self.player_max_tp.to_string()],
+                ),
+                (
+                    "battle-info-attack".into(),
+                    vec![self.player.effective_attack().to_string()],
+                ),
+                (
+                    "battle-info-defense".into(),
+                    vec![self.player.effective_defense().to_string()],
+                ),
+            ],
+            description_key: self.player_data.opening_introduction_text.to_owned(),
+            modifiers: self.player.modifiers.view_items(),
+        }
+    }
+
+    fn enemy_info(&self, index: usize) -> InfoWindowData {
+        let EnemyBattler { data, status, .. } = &self.enemies[index];
+        InfoWindowData {
+            title_key: data.enemy_name.to_owned(),
+            lines: vec![
+                (
+                    "battle-info-hp".into(),
+                    vec![status.hp.to_string(), status.max_hp.to_string()],
+                ),
+                (
+                    "battle-info-attack".into(),
+                    vec![status.effective_attack().to_string()],
+                ),
+                (
+                    "battle-info-defense".into(),
+                    vec![status.effective_defense().to_string()],
+                ),
+            ],
+            description_key: data.description.to_owned(),
+            modifiers: status.modifiers.view_items(),
+        }
+    }
+
+    pub(super) fn turn_count(&self) -> u32 {
         self.battle_state.get("bi-turn-count") as u32
     }
 
-    pub(super) fn process_turn(
+    pub(super) fn turn_start(
         &mut self,
         player_state: &mut PlayerState,
-        command: BattleCommand,
+        rng: &mut impl Rng,
     ) -> (Vec<BattleViewCommand>, BattleTurnResult) {
-        let mut view_command = vec![];
+        let mut view_commands = vec![];
+        self.battle_state.add("bi-turn-count", 1);
+
+        self.tick_modifiers(Side::Player, &mut view_commands);
+        for index in self.alive_enemies() {
+            self.tick_modifiers(Side::Enemy(index), &mut view_commands);
+        }
+
+        let result = self.check_result(player_state, rng, &mut view_commands);
+        if let BattleTurnResult::Continue = result {
+            self.check_phase(&mut view_commands);
+        }
+        (view_commands, result)
+    }
+
+    fn element_rate(&self, target: Side, element: Option<Element>) -> f64 {
+        match (target, element) {
+            (Side::Enemy(index), Some(element)) => self.enemies[index]
+                .data
+                .element_rates
+                .as_ref()
+                .and_then(|rates| rates.get(&element).copied())
+                .unwrap_or(1.0),
+            _ => 1.0,
+        }
+    }
+
+    /// 回避、会心、属性倍率、ガードの順に判定する。必中状態の攻撃は回避されない
+    fn damage(
+        &mut self,
+        user: Side,
+        target: Side,
+        multiplier: f64,
+        element: Option<Element>,
+        rng: &mut impl Rng,
+        view_commands: &mut Vec<BattleViewCommand>,
+    ) {
+        let sure_hit = self.battler(user).modifiers.value(ModifierKind::SureHit) > 0;
+        if !sure_hit && rng.gen_bool(self.battler(target).evasion_rate.clamp(0.0, 1.0)) {
+            view_commands.push(match target {
+                Side::Player => BattleViewCommand::PlayerMiss,
+                Side::Enemy(index) => BattleViewCommand::EnemyMiss { index },
+            });
+            return;
+        }
+
+        let mut multiplier = multiplier;
+        if rng.gen_bool(self.battler(user).critical_rate.clamp(0.0, 1.0)) {
+            multiplier *= CRITICAL_MULTIPLIER;
+            view_commands.push(BattleViewCommand::Critical);
+        }
+        let element_rate = self.element_rate(target, element);
+        if element_rate > 1.0 {
+            view_commands.push(BattleViewCommand::Weak);
+        }
+
+        let damage = calc_damage(
+            self.battler(user).effective_attack(),
+            self.battler(target).effective_defense(),
+            multiplier,
+        );
+        let damage = ((damage as f64 * element_rate).round() as i32).max(1);
+        let damage = (damage * (100 - self.battler(target).guard_rate()) / 100).max(1);
+        self.inflict_damage(target, damage, view_commands);
+    }
+
+    fn inflict_damage(
+        &mut self,
+        target: Side,
+        damage: i32,
+        view_commands: &mut Vec<BattleViewCommand>,
+    ) {
+        let status = self.battler_mut(target);
+        status.hp = (status.hp - damage).max(0);
+        let (hp, max_hp) = (status.hp, status.max_hp);
+        view_commands.push(match target {
+            Side::Player => BattleViewCommand::PlayerDamage { damage, hp, max_hp },
+            Side::Enemy(index) => BattleViewCommand::EnemyDamage {
+                index,
+                damage,
+                hp,
+                max_hp,
+            },
+        });
+    }
+
+    fn heal(&mut self, user: Side, amount: i32, view_commands: &mut Vec<BattleViewCommand>) {
+        let status = self.battler_mut(user);
+        let heal = amount.min(status.max_hp - status.hp).max(0);
+        status.hp += heal;
+        let (hp, max_hp) = (status.hp, status.max_hp);
+        view_commands.push(match user {
+            Side::Player => BattleViewCommand::PlayerHeal { heal, hp, max_hp },
+            Side::Enemy(index) => BattleViewCommand::EnemyHeal {
+                index,
+                heal,
+                hp,
+                max_hp,
+            },
+        });
+    }
+
+    fn gain_tp(&mut self, user: Side, amount: i32, view_commands: &mut Vec<BattleViewCommand>) {
+        if user == Side::Player {
+            self.player_tp = (self.player_tp + amount).clamp(0, self.player_max_tp);
+            view_commands.push(BattleViewCommand::PlayerSetTp {
+                tp: self.player_tp,
+                max_tp: self.player_max_tp,
+            });
+        }
+    }
+
+    fn modifiers_view_command(&self, side: Side) -> BattleViewCommand {
+        let items = self.battler(side).modifiers.view_items();
+        match side {
+            Side::Player => BattleViewCommand::PlayerModifiers { items },
+            Side::Enemy(index) => BattleViewCommand::EnemyModifiers { index, items },
+        }
+    }
 
-        view_command.push(BattleViewCommand::Message {
-            key: "test-attack".into(),
-            args: &[],
+    fn add_modifier(
+        &mut self,
+        target: Side,
+        kind: ModifierKind,
+        value: i32,
+        turns: u32,
+        view_commands: &mut Vec<BattleViewCommand>,
+    ) {
+        self.battler_mut(target).modifiers.add(kind, value, turns);
+        view_commands.push(self.modifiers_view_command(target));
+        view_commands.push(BattleViewCommand::Message {
+            key: "battle-message-modifier-added".into(),
+            args: vec![
+                self.battler_name(target),
+                (self.get_text)(&format!("modifier-name-{}", modifier_key(kind))),
+            ],
         });
-        view_command.push(BattleViewCommand::PlayerSetTp { tp: 35, max_tp: 50 });
-        view_command.push(BattleViewCommand::PlayerBlink);
-        view_command.push(BattleViewCommand::EnemyDamage {
-            damage: 50,
-            hp: 200,
-            max_hp: 250,
+        view_commands.push(BattleViewCommand::WaitKey);
+    }
+
+    fn cure(
+        &mut self,
+        target: Side,
+        modifiers: &[ModifierKind],
+        view_commands: &mut Vec<BattleViewCommand>,
+    ) {
+        for &kind in modifiers {
+            if self.battler_mut(target).modifiers.remove(kind) {
+                view_commands.push(self.modifiers_view_command(target));
+                view_commands.push(BattleViewCommand::Message {
+                    key: "battle-message-modifier-expired".into(),
+                    args: vec![
+                        self.battler_name(target),
+                        (self.get_text)(&format!("modifier-name-{}", modifier_key(kind))),
+                    ],
+                });
+                view_commands.push(BattleViewCommand::WaitKey);
+            }
+        }
+    }
+
+    fn tick_modifiers(&mut self, side: Side, view_commands: &mut Vec<BattleViewCommand>) {
+        let poison = self.battler(side).modifiers.value(ModifierKind::Poison);
+        if poison > 0 {
+            view_commands.push(BattleViewCommand::Message {
+                key: "battle-message-poison-damage".into(),
+                args: vec![self.battler_name(side)],
+            });
+            self.inflict_damage(side, poison, view_commands);
+            view_commands.push(BattleViewCommand::WaitKey);
+        }
+        let regen = self.battler(side).modifiers.value(ModifierKind::Regen);
+        if regen > 0 && self.battler(side).hp > 0 {
+            view_commands.push(BattleViewCommand::Message {
+                key: "battle-message-regen-heal".into(),
+                args: vec![self.battler_name(side)],
+            });
+            self.heal(side, regen, view_commands);
+            view_commands.push(BattleViewCommand::WaitKey);
+        }
+
+        let expired = self.battler_mut(side).modifiers.tick();
+        view_commands.push(self.modifiers_view_command(side));
+        for kind in expired {
+            view_commands.push(BattleViewCommand::Message {
+                key: "battle-message-modifier-expired".into(),
+                args: vec![
+                    self.battler_name(side),
+                    (self.get_text)(&format!("modifier-name-{}", modifier_key(kind))),
+                ],
+            });
+            view_commands.push(BattleViewCommand::WaitKey);
+        }
+    }
+
+    fn try_revive(
+        &mut self,
+        player_state: &mut PlayerState,
+        rng: &mut impl Rng,
+        view_commands: &mut Vec<BattleViewCommand>,
+    ) -> bool {
+        let item_data = player_state
+            .get_items()
+            .iter()
+            .filter(|&&(_, count)| count > 0)
+            .filter_map(|&(item_id, _)| self.item_data.iter().find(|i| i.id == item_id))
+            .find(|i| i.use_on_down == Some(true));
+        if let Some(item_data) = item_data {
+            if player_state.use_item(item_data.id).is_ok() {
+                view_commands.push(BattleViewCommand::Message {
+                    key: "battle-message-player-revive".into(),
+                    args: vec![(self.get_text)(&item_data.item_name)],
+                });
+                view_commands.push(BattleViewCommand::WaitKey);
+                self.apply_effects(
+                    Side::Player,
+                    Side::Player,
+                    &item_data.effects,
+                    None,
+                    rng,
+                    view_commands,
+                );
+                view_commands.push(BattleViewCommand::WaitKey);
+                return true;
+            }
+        }
+        false
+    }
+
+    fn check_result(
+        &mut self,
+        player_state: &mut PlayerState,
+        rng: &mut impl Rng,
+        view_commands: &mut Vec<BattleViewCommand>,
+    ) -> BattleTurnResult {
+        for index in self.alive_enemies() {
+            if self.enemies[index].status.hp == 0 {
+                self.enemies[index].down = true;
+                view_commands.push(BattleViewCommand::EnemyDown { index });
+                view_commands.push(BattleViewCommand::WaitKey);
+            }
+        }
+
+        if self.alive_enemies().is_empty() {
+            BattleTurnResult::Win
+        } else if self.player.hp == 0 && !self.try_revive(player_state, rng, view_commands) {
+            view_commands.push(BattleViewCommand::Message {
+                key: "battle-message-player-down".into(),
+                args: vec![],
+            });
+            view_commands.push(BattleViewCommand::WaitKey);
+            BattleTurnResult::Lose
+        } else {
+            BattleTurnResult::Continue
+        }
+    }
+
+    fn apply_effects(
+        &mut self,
+        user: Side,
+        target: Side,
+        effects: &'a [Effect],
+        element: Option<Element>,
+        rng: &mut impl Rng,
+        view_commands: &mut Vec<BattleViewCommand>,
+    ) {
+        for effect in effects {
+            match effect {
+                &Effect::Damage { multiplier } => {
+                    if self.battler(target).hp > 0 {
+                        self.damage(user, target, multiplier, element, rng, view_commands)
+                    }
+                }
+                &Effect::MultiHit { multiplier, hits } => {
+                    for i in 0..hits {
+                        if self.battler(target).hp == 0 {
+                            break;
+                        }
+                        if i > 0 {
+                            view_commands.push(BattleViewCommand::Delay { millis: 450 });
+                        }
+                        self.damage(user, target, multiplier, element, rng, view_commands);
+                    }
+                }
+                &Effect::Heal { amount } => self.heal(user, amount, view_commands),
+                &Effect::HealRatio { ratio } => {
+                    let amount = (self.battler(user).max_hp as f64 * ratio).round() as i32;
+                    self.heal(user, amount, view_commands)
+                }
+                &Effect::GainTp { amount } => self.gain_tp(user, amount, view_commands),
+                &Effect::Buff {
+                    modifier,
+                    value,
+                    turns,
+                } => self.add_modifier(user, modifier, value, turns, view_commands),
+                &Effect::Debuff {
+                    modifier,
+                    value,
+                    turns,
+                } => self.add_modifier(target, modifier, value, turns, view_commands),
+                Effect::Cure { modifiers } => self.cure(user, modifiers, view_commands),
+                Effect::SetState { key, value } => self.battle_state_mut(user).set(key, *value),
+                Effect::AddState { key, value } => self.battle_state_mut(user).add(key, *value),
+            }
+        }
+    }
+
+    fn player_skill(
+        &mut self,
+        skill_id: SkillId,
+        target: usize,
+        rng: &mut impl Rng,
+        view_commands: &mut Vec<BattleViewCommand>,
+    ) {
+        let skill_data = self
+            .player_data
+            .skills
+            .iter()
+            .find(|&s| s.id == skill_id)
+            .unwrap();
+
+        if !self.can_afford(skill_data.skill_cost) {
+            warn!("Not enough TP for skill: {:?}", skill_id);
+            view_commands.push(BattleViewCommand::Message {
+                key: "battle-message-not-enough-tp".into(),
+                args: vec![],
+            });
+            view_commands.push(BattleViewCommand::WaitKey);
+            return;
+        }
+
+        if skill_id == SkillId(0) {
+            view_commands.push(BattleViewCommand::Message {
+                key: "battle-message-player-attack".into(),
+                args: vec![],
+            });
+        } else {
+            view_commands.push(BattleViewCommand::Message {
+                key: "battle-message-player-skill".into(),
+                args: vec![(self.get_text)(&skill_data.skill_name)],
+            });
+        }
+
+        if let SkillCost::Cost(cost) = skill_data.skill_cost {
+            if cost > 0 {
+                self.gain_tp(Side::Player, -(cost as i32), view_commands);
+            }
+        }
+
+        view_commands.push(BattleViewCommand::PlayerBlink);
+        self.apply_effects(
+            Side::Player,
+            Side::Enemy(target),
+            &skill_data.effects,
+            skill_data.element,
+            rng,
+            view_commands,
+        );
+        view_commands.push(BattleViewCommand::Delay { millis: 300 });
+    }
+
+    fn player_item(
+        &mut self,
+        item_id: ItemId,
+        target: usize,
+        rng: &mut impl Rng,
+        view_commands: &mut Vec<BattleViewCommand>,
+    ) {
+        let item_data = self.item_data.iter().find(|&i| i.id == item_id).unwrap();
+
+        view_commands.push(BattleViewCommand::Message {
+            key: "battle-message-player-item".into(),
+            args: vec![(self.get_text)(&item_data.item_name)],
         });
-        view_command.push(BattleViewCommand::Delay { millis: 450 });
-        view_command.push(BattleViewCommand::EnemyDamage {
-            damage: 50,
-            hp: 150,
-            max_hp: 250,
+        view_commands.push(BattleViewCommand::PlayerBlink);
+        self.apply_effects(
+            Side::Player,
+            Side::Enemy(target),
+            &item_data.effects,
+            None,
+            rng,
+            view_commands,
+        );
+        view_commands.push(BattleViewCommand::Delay { millis: 300 });
+    }
+
+    /// bi-から始まるキーは戦闘全体で共有しているストアから読む
+    fn enemy_state(&self, index: usize, key: &'a str) -> i32 {
+        if key.starts_with("bi-") {
+            self.battle_state.get(key)
+        } else {
+            self.enemies[index].battle_state.get(key)
+        }
+    }
+
+    fn check_condition(&self, index: usize, condition: &'a EnemyActionCondition) -> bool {
+        let status = &self.enemies[index].status;
+        match condition {
+            EnemyActionCondition::HpBelow { ratio } => {
+                (status.hp as f64) < status.max_hp as f64 * ratio
+            }
+            EnemyActionCondition::PlayerHpBelow { ratio } => {
+                (self.player.hp as f64) < self.player.max_hp as f64 * ratio
+            }
+            EnemyActionCondition::StateEquals { key, value } => {
+                self.enemy_state(index, key) == *value
+            }
+            EnemyActionCondition::StateAtLeast { key, value } => {
+                self.enemy_state(index, key) >= *value
+            }
+            EnemyActionCondition::StateBelow { key, value } => {
+                self.enemy_state(index, key) < *value
+            }
+        }
+    }
+
+    fn check_phase(&mut self, view_commands: &mut Vec<BattleViewCommand>) {
+        for index in self.alive_enemies() {
+            let data = self.enemies[index].data;
+            let phases = match &data.phases {
+                Some(phases) => phases,
+                None => continue,
+            };
+            let (hp, max_hp) = self.enemy_hp(index);
+            let ratio = hp as f64 / max_hp as f64;
+            let next_phase = phases
+                .iter()
+                .enumerate()
+                .filter(|(_, phase)| ratio < phase.hp_below)
+                .map(|(index, _)| index)
+                .next_back();
+            if let Some(phase) = next_phase {
+                if Some(phase) <= self.enemies[index].phase {
+                    continue;
+                }
+                self.enemies[index].phase = Some(phase);
+                view_commands.push(BattleViewCommand::EnemyPhaseChange { index });
+                view_commands.push(BattleViewCommand::Message {
+                    key: phases[phase].message.to_owned(),
+                    args: vec![self.battler_name(Side::Enemy(index))],
+                });
+                view_commands.push(BattleViewCommand::WaitKey);
+            }
+        }
+    }
+
+    fn select_enemy_action(&mut self, index: usize, rng: &mut impl Rng) -> &'a EnemyAction {
+        let data = self.enemies[index].data;
+        let mut pattern = match (&data.phases, self.enemies[index].phase) {
+            (Some(phases), Some(phase)) => &phases[phase].action_pattern,
+            _ => &data.action_pattern,
+        };
+        loop {
+            match pattern {
+                EnemyActionPattern::Action(action) => return action,
+                EnemyActionPattern::Weighted { branches } => {
+                    let dist = WeightedIndex::new(branches.iter().map(|b| b.weight)).unwrap();
+                    pattern = &branches[dist.sample(rng)].pattern;
+                }
+                EnemyActionPattern::Conditional {
+                    branches,
+                    otherwise,
+                } => {
+                    pattern = branches
+                        .iter()
+                        .find(|b| self.check_condition(index, &b.condition))
+                        .map(|b| &b.pattern)
+                        .unwrap_or(otherwise);
+                }
+                EnemyActionPattern::Rotation { key, patterns } => {
+                    let battle_state = &mut self.enemies[index].battle_state;
+                    let rotation = battle_state.get(key) as usize % patterns.len();
+                    battle_state.add(key, 1);
+                    pattern = &patterns[rotation];
+                }
+            }
+        }
+    }
+
+    fn enemy_action(
+        &mut self,
+        index: usize,
+        rng: &mut impl Rng,
+        view_commands: &mut Vec<BattleViewCommand>,
+    ) {
+        let action = self.select_enemy_action(index, rng);
+        self.last_attacker = index;
+
+        view_commands.push(BattleViewCommand::Message {
+            key: action.message.to_owned(),
+            args: vec![self.battler_name(Side::Enemy(index))],
         });
-        view_command.push(BattleViewCommand::Delay { millis: 300 });
+        view_commands.push(BattleViewCommand::WaitKey);
+        view_commands.push(BattleViewCommand::EnemyBlink { index });
+        self.apply_effects(
+            Side::Enemy(index),
+            Side::Player,
+            &action.effects,
+            None,
+            rng,
+            view_commands,
+        );
+        if !matches!(view_commands.last(), Some(BattleViewCommand::WaitKey)) {
+            view_commands.push(BattleViewCommand::WaitKey);
+        }
+        view_commands.push(BattleViewCommand::Delay { millis: 300 });
+    }
 
-        view_command.push(BattleViewCommand::Message {
-            key: "test-enemy-attack".into(),
-            args: &["森林チョウ"],
+    /// 逃走の成功率は攻撃力の比から求め、失敗するたびに上がる
+    fn escape_rate(&self) -> f64 {
+        let player_attack = self.player.effective_attack().max(1) as f64;
+        let enemy_attack = self
+            .alive_enemies()
+            .iter()
+            .map(|&index| self.enemies[index].status.effective_attack().max(1))
+            .sum::<i32>() as f64;
+        let failed = self.battle_state.get("bi-escape-failed") as f64;
+        (player_attack / (player_attack + enemy_attack) + 0.15 * failed).clamp(0.1, 0.95)
+    }
+
+    fn escape(&mut self, rng: &mut impl Rng, view_commands: &mut Vec<BattleViewCommand>) -> bool {
+        view_commands.push(BattleViewCommand::Message {
+            key: "battle-message-escape".into(),
+            args: vec![],
         });
-        view_command.push(BattleViewCommand::WaitKey);
-        view_command.push(BattleViewCommand::EnemyBlink);
-        view_command.push(BattleViewCommand::PlayerDamage {
-            damage: 40,
-            hp: 110,
-            max_hp: 150,
+        view_commands.push(BattleViewCommand::WaitKey);
+
+        let cannot_escape = self
+            .alive_enemies()
+            .iter()
+            .any(|&index| self.enemies[index].data.cannot_escape == Some(true));
+        let key = if cannot_escape {
+            "battle-message-cannot-escape"
+        } else if rng.gen_bool(self.escape_rate()) {
+            view_commands.push(BattleViewCommand::Message {
+                key: "battle-message-escape-success".into(),
+                args: vec![],
+            });
+            view_commands.push(BattleViewCommand::WaitKey);
+            return true;
+        } else {
+            self.battle_state.add("bi-escape-failed", 1);
+            "battle-message-escape-failed"
+        };
+        view_commands.push(BattleViewCommand::Message {
+            key: key.into(),
+            args: vec![],
         });
-        view_command.push(BattleViewCommand::WaitKey);
-        view_command.push(BattleViewCommand::Delay { millis: 300 });
+        view_commands.push(BattleViewCommand::WaitKey);
+        false
+    }
 
-        view_command.push(BattleViewCommand::Delay { millis: 300 });
+    pub(super) fn process_turn(
+        &mut self,
+        player_state: &mut PlayerState,
+        command: BattleCommand,
+        rng: &mut impl Rng,
+    ) -> (Vec<BattleViewCommand>, BattleTurnResult) {
+        let mut view_commands = vec![];
+
+        match command {
+            BattleCommand::Skill(skill_id, target) => {
+                self.player_skill(skill_id, target, rng, &mut view_commands)
+            }
+            BattleCommand::Item(item_id, target) => match player_state.use_item(item_id) {
+                Ok(()) => self.player_item(item_id, target, rng, &mut view_commands),
+                Err(err) => {
+                    warn!("{}", err);
+                    view_commands.push(BattleViewCommand::Message {
+                        key: "battle-message-item-not-owned".into(),
+                        args: vec![],
+                    });
+                    view_commands.push(BattleViewCommand::WaitKey);
+                }
+            },
+            BattleCommand::Escape => {
+                if self.escape(rng, &mut view_commands) {
+                    return (view_commands, BattleTurnResult::Escaped);
+                }
+            }
+        }
+
+        match self.check_result(player_state, rng, &mut view_commands) {
+            BattleTurnResult::Continue => (),
+            result => return (view_commands, result),
+        }
+        self.check_phase(&mut view_commands);
+
+        for index in self.alive_enemies() {
+            self.enemy_action(index, rng, &mut view_commands);
+            match self.check_result(player_state, rng, &mut view_commands) {
+                BattleTurnResult::Continue => (),
+                result => return (view_commands, result),
+            }
+        }
+        (view_commands, BattleTurnResult::Continue)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn load_game_data() -> GameData {
+        GameData::load_from_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/resources")).unwrap()
+    }
+
+    /// 最初のプレイヤーで最初の敵に通常攻撃を1ターン行い、(プレイヤーのHP, 敵のHP)を返す
+    fn attack_once(game_data: &GameData, seed: u64) -> ((i32, i32), (i32, i32)) {
+        let player_data = &game_data.player_data()[0];
+        let enemy_data = [&game_data.enemy_data()[0]];
+        let mut player_state = PlayerState::new(player_data);
+        let mut model = BattleModel::new(
+            player_data,
+            game_data.item_data(),
+            &enemy_data,
+            &player_state,
+            |key| key.to_owned(),
+        );
+        let mut rng = StdRng::seed_from_u64(seed);
+
+        let (_, result) = model.turn_start(&mut player_state, &mut rng);
+        assert!(matches!(result, BattleTurnResult::Continue));
+        assert_eq!(model.turn_count(), 1);
+        // 敵の行動条件からもbi-のキーが見えている
+        assert_eq!(model.enemy_state(0, "bi-turn-count"), 1);
+
+        let (view_commands, _) = model.process_turn(
+            &mut player_state,
+            BattleCommand::Skill(SkillId(0), 0),
+            &mut rng,
+        );
+        assert!(!view_commands.is_empty());
+        (model.player_hp(), model.enemy_hp(0))
+    }
+
+    #[test]
+    fn it_should_resolve_same_turn_with_same_seed() {
+        let game_data = load_game_data();
+        for seed in 0..20 {
+            assert_eq!(attack_once(&game_data, seed), attack_once(&game_data, seed));
+        }
+    }
+
+    #[test]
+    fn it_should_resolve_fixed_seed_to_exact_hp() {
+        let game_data = load_game_data();
+        assert_eq!(attack_once(&game_data, 0), ((360, 400), (205, 250)));
+    }
+
+    #[test]
+    fn it_should_win_when_enemy_hp_reaches_zero() {
+        let game_data = load_game_data();
+        assert_eq!(fight(&game_data, 0, 0), (BattleTurnResult::Win, 7, 220, 0));
+    }
+
+    #[test]
+    fn it_should_lose_when_player_hp_reaches_zero() {
+        let game_data = load_game_data();
+        assert_eq!(fight(&game_data, 1, 0), (BattleTurnResult::Lose, 9, 0, 520));
+    }
+
+    /// 最初のプレイヤーでenemy番目の敵に戦闘が終わるまで通常攻撃を続け、(結果, ターン数, プレイヤーのHP, 敵のHP)を返す
+    fn fight(game_data: &GameData, enemy: usize, seed: u64) -> (BattleTurnResult, u32, i32, i32) {
+        let player_data = &game_data.player_data()[0];
+        let enemy_data = [&game_data.enemy_data()[enemy]];
+        let mut player_state = PlayerState::new(player_data);
+        let mut model = BattleModel::new(
+            player_data,
+            game_data.item_data(),
+            &enemy_data,
+            &player_state,
+            |key| key.to_owned(),
+        );
+        let mut rng = StdRng::seed_from_u64(seed);
+        let result = loop {
+            let (_, result) = model.turn_start(&mut player_state, &mut rng);
+            if !matches!(result, BattleTurnResult::Continue) {
+                break result;
+            }
+            let (_, result) = model.process_turn(
+                &mut player_state,
+                BattleCommand::Skill(SkillId(0), 0),
+                &mut rng,
+            );
+            if !matches!(result, BattleTurnResult::Continue) {
+                break result;
+            }
+        };
+        (
+            result,
+            model.turn_count(),
+            model.player_hp().0,
+            model.enemy_hp(0).0,
+        )
+    }
+
+    #[test]
+    fn it_should_not_use_skill_without_enough_tp() {
+        let game_data = load_game_data();
+        let player_data = &game_data.player_data()[0];
+        let enemy_data = [&game_data.enemy_data()[0]];
+        let mut player_state = PlayerState::new(player_data);
+        player_state.add_skill(SkillId(10), &player_data.skills);
+        let mut model = BattleModel::new(
+            player_data,
+            game_data.item_data(),
+            &enemy_data,
+            &player_state,
+            |key| key.to_owned(),
+        );
+        let mut rng = StdRng::seed_from_u64(0);
+        model.turn_start(&mut player_state, &mut rng);
 
-        (view_command, BattleTurnResult::Continue)
+        let (view_commands, _) = model.process_turn(
+            &mut player_state,
+            BattleCommand::Skill(SkillId(10), 0),
+            &mut rng,
+        );
+        assert!(view_commands.iter().any(|c| matches!(
+            c,
+            BattleViewCommand::Message { key, .. } if key == "battle-message-not-enough-tp"
+        )));
+        assert_eq!(model.enemy_hp(0).0, enemy_data[0].max_hp);
     }
 }
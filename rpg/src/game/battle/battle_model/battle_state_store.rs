@@ -2,6 +2,11 @@ use std::collections::HashMap;
 
 /// ## Built-in
 /// - bi-turn-count ターン数
+/// - bi-escape-failed 逃走に失敗した回数
+///
+/// ## Enemy
+/// - 敵ごとに独立したストアを持つ。条件のbi-キーはBuilt-inのストアを読む
+/// - 敵データのRotationのキー、SetState/AddStateで書き換えるキー
 ///
 /// ## Item
 ///
@@ -25,6 +30,10 @@ impl<'a> BattleStateStore<'a> {
         entry.and_modify(|v| *v += value).or_insert(value);
     }
 
+    pub(super) fn set(&mut self, key: &'a str, value: i32) {
+        self.hash_map.insert(key, value);
+    }
+
     pub(super) fn get(&self, key: &'a str) -> i32 {
         self.hash_map.get(key).copied().unwrap_or(0)
     }
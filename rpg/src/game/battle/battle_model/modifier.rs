@@ -0,0 +1,196 @@
+use crate::game::battle::battle_view::ModifierViewItem;
+use crate::game_data::*;
+
+const ACCUMULATE_MAX: i32 = 100;
+
+/// 同種のモディファイアが重ねがけされたときの規則
+enum StackRule {
+    /// 効果量は大きい方、ターン数は加算
+    Extend,
+    /// 効果量・ターン数ともに大きい方
+    Refresh,
+    /// 効果量は加算(上限あり)、ターン数は大きい方
+    Accumulate,
+}
+
+fn stack_rule(kind: ModifierKind) -> StackRule {
+    match kind {
+        ModifierKind::Poison => StackRule::Extend,
+        ModifierKind::Regen => StackRule::Refresh,
+        ModifierKind::AttackUp => StackRule::Accumulate,
+        ModifierKind::AttackDown => StackRule::Accumulate,
+        ModifierKind::DefenseUp => StackRule::Accumulate,
+        ModifierKind::DefenseDown => StackRule::Accumulate,
+        ModifierKind::Guard => StackRule::Refresh,
+        ModifierKind::SureHit => StackRule::Refresh,
+    }
+}
+
+pub(super) fn modifier_key(kind: ModifierKind) -> &'static str {
+    match kind {
+        ModifierKind::Poison => "poison",
+        ModifierKind::Regen => "regen",
+        ModifierKind::AttackUp => "attack-up",
+        ModifierKind::AttackDown => "attack-down",
+        ModifierKind::DefenseUp => "defense-up",
+        ModifierKind::DefenseDown => "defense-down",
+        ModifierKind::Guard => "guard",
+        ModifierKind::SureHit => "sure-hit",
+    }
+}
+
+struct Modifier {
+    kind: ModifierKind,
+    value: i32,
+    turns: u32,
+}
+
+pub(super) struct Modifiers {
+    modifiers: Vec<Modifier>,
+}
+impl Modifiers {
+    pub(super) fn new() -> Self {
+        Self { modifiers: vec![] }
+    }
+
+    pub(super) fn add(&mut self, kind: ModifierKind, value: i32, turns: u32) {
+        if let Some(m) = self.modifiers.iter_mut().find(|m| m.kind == kind) {
+            match stack_rule(kind) {
+                StackRule::Extend => {
+                    m.value = m.value.max(value);
+                    m.turns += turns;
+                }
+                StackRule::Refresh => {
+                    m.value = m.value.max(value);
+                    m.turns = m.turns.max(turns);
+                }
+                StackRule::Accumulate => {
+                    m.value = (m.value + value).min(ACCUMULATE_MAX);
+                    m.turns = m.turns.max(turns);
+                }
+            }
+        } else {
+            self.modifiers.push(Modifier { kind, value, turns });
+        }
+    }
+
+    pub(super) fn remove(&mut self, kind: ModifierKind) -> bool {
+        let len = self.modifiers.len();
+        self.modifiers.retain(|m| m.kind != kind);
+        self.modifiers.len() != len
+    }
+
+    pub(super) fn value(&self, kind: ModifierKind) -> i32 {
+        self.modifiers
+            .iter()
+            .find(|m| m.kind == kind)
+            .map(|m| m.value)
+            .unwrap_or(0)
+    }
+
+    /// ターン数を1減らし、切れたモディファイアの種類を返す
+    pub(super) fn tick(&mut self) -> Vec<ModifierKind> {
+        for m in self.modifiers.iter_mut() {
+            m.turns = m.turns.saturating_sub(1);
+        }
+        let expired = self
+            .modifiers
+            .iter()
+            .filter(|m| m.turns == 0)
+            .map(|m| m.kind)
+            .collect();
+        self.modifiers.retain(|m| m.turns > 0);
+        expired
+    }
+
+    pub(super) fn view_items(&self) -> Vec<ModifierViewItem> {
+        self.modifiers
+            .iter()
+            .map(|m| {
+                let key = modifier_key(m.kind);
+                ModifierViewItem {
+                    name_key: format!("modifier-name-{}", key),
+                    turns: m.turns,
+                    description_key: format!("modifier-description-{}", key),
+                    description_args: vec![m.value.to_string()],
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn turns(modifiers: &Modifiers, kind: ModifierKind) -> Option<u32> {
+        modifiers
+            .modifiers
+            .iter()
+            .find(|m| m.kind == kind)
+            .map(|m| m.turns)
+    }
+
+    #[test]
+    fn it_should_extend_turns_and_keep_larger_value() {
+        let mut modifiers = Modifiers::new();
+        modifiers.add(ModifierKind::Poison, 10, 3);
+        modifiers.add(ModifierKind::Poison, 5, 2);
+        assert_eq!(modifiers.value(ModifierKind::Poison), 10);
+        assert_eq!(turns(&modifiers, ModifierKind::Poison), Some(5));
+    }
+
+    #[test]
+    fn it_should_refresh_to_larger_value_and_turns() {
+        let mut modifiers = Modifiers::new();
+        modifiers.add(ModifierKind::Regen, 5, 4);
+        modifiers.add(ModifierKind::Regen, 8, 2);
+        assert_eq!(modifiers.value(ModifierKind::Regen), 8);
+        assert_eq!(turns(&modifiers, ModifierKind::Regen), Some(4));
+    }
+
+    #[test]
+    fn it_should_accumulate_value_up_to_max() {
+        let mut modifiers = Modifiers::new();
+        modifiers.add(ModifierKind::AttackUp, 30, 2);
+        modifiers.add(ModifierKind::AttackUp, 30, 3);
+        assert_eq!(modifiers.value(ModifierKind::AttackUp), 60);
+        assert_eq!(turns(&modifiers, ModifierKind::AttackUp), Some(3));
+        modifiers.add(ModifierKind::AttackUp, 60, 1);
+        assert_eq!(modifiers.value(ModifierKind::AttackUp), ACCUMULATE_MAX);
+    }
+
+    #[test]
+    fn it_should_keep_kinds_separate() {
+        let mut modifiers = Modifiers::new();
+        modifiers.add(ModifierKind::AttackUp, 20, 2);
+        modifiers.add(ModifierKind::AttackDown, 10, 2);
+        assert_eq!(modifiers.value(ModifierKind::AttackUp), 20);
+        assert_eq!(modifiers.value(ModifierKind::AttackDown), 10);
+        assert_eq!(modifiers.value(ModifierKind::Guard), 0);
+    }
+
+    #[test]
+    fn it_should_expire_modifiers_when_turns_run_out() {
+        let mut modifiers = Modifiers::new();
+        modifiers.add(ModifierKind::Poison, 10, 1);
+        modifiers.add(ModifierKind::Guard, 50, 2);
+
+        assert_eq!(modifiers.tick(), vec![ModifierKind::Poison]);
+        assert_eq!(modifiers.value(ModifierKind::Poison), 0);
+        assert_eq!(turns(&modifiers, ModifierKind::Guard), Some(1));
+
+        assert_eq!(modifiers.tick(), vec![ModifierKind::Guard]);
+        assert!(modifiers.tick().is_empty());
+        assert!(modifiers.view_items().is_empty());
+    }
+
+    #[test]
+    fn it_should_remove_modifier() {
+        let mut modifiers = Modifiers::new();
+        modifiers.add(ModifierKind::DefenseDown, 20, 3);
+        assert!(modifiers.remove(ModifierKind::DefenseDown));
+        assert!(!modifiers.remove(ModifierKind::DefenseDown));
+        assert_eq!(modifiers.value(ModifierKind::DefenseDown), 0);
+    }
+}
@@ -0,0 +1,70 @@
+use rand::distributions::*;
+use rand::prelude::*;
+
+use crate::game::PlayerState;
+use crate::game_data::*;
+
+/// 勝利時に得られる報酬。スキルは候補のみで、習得するかは探索画面で選ぶ
+pub struct BattleRewards {
+    pub gold: u32,
+    pub items: Vec<(ItemId, u32)>,
+    pub skill_candidates: Vec<SkillId>,
+}
+impl BattleRewards {
+    pub(super) fn roll(
+        enemy_data: &[&EnemyData],
+        player_data: &PlayerData,
+        player_state: &PlayerState,
+        rng: &mut impl Rng,
+    ) -> Self {
+        let mut gold = 0;
+        let mut items: Vec<(ItemId, u32)> = vec![];
+        for drops in enemy_data.iter().filter_map(|e| e.drops.as_ref()) {
+            gold += drops.gold.unwrap_or(0);
+            if let Some(item_drops) = &drops.items {
+                let dist = WeightedIndex::new(item_drops.iter().map(|d| d.weight)).unwrap();
+                for _ in 0..drops.rolls.unwrap_or(1) {
+                    let drop = &item_drops[dist.sample(rng)];
+                    if let Some(item_id) = drop.item_id {
+                        let count = drop.count.unwrap_or(1);
+                        match items.iter_mut().find(|(id, _)| *id == item_id) {
+                            Some((_, c)) => *c += count,
+                            None => items.push((item_id, count)),
+                        }
+                    }
+                }
+            }
+        }
+        items.sort_by_key(|(id, _)| id.0);
+
+        // スキルの候補は複数の敵が持っていても先頭の敵のものだけを使う
+        let skill_candidates = enemy_data
+            .iter()
+            .find_map(|e| e.drops.as_ref().and_then(|d| d.skills.as_ref()))
+            .map(|skill_drop| {
+                player_state.pick_skill_candidates(
+                    &skill_drop.skills,
+                    skill_drop.count,
+                    &player_data.skills,
+                    rng,
+                )
+            })
+            .unwrap_or_default();
+
+        Self {
+            gold,
+            items,
+            skill_candidates,
+        }
+    }
+
+    /// ゴールドとアイテムを反映する。スキルの候補は呼び出し側で提示する
+    pub fn apply(&self, player_state: &mut PlayerState) {
+        player_state.add_gold(self.gold);
+        for &(item_id, count) in self.items.iter() {
+            for _ in 0..count {
+                player_state.add_item(item_id);
+            }
+        }
+    }
+}
@@ -1,17 +1,28 @@
 use animation_engine::executor::*;
 use animation_engine::*;
 use futures::join;
+use rand::prelude::*;
 use std::time::Duration;
 
 use crate::game;
 use crate::game::battle::battle_model::*;
 use crate::game::battle::battle_view::*;
+use crate::game::battle::BattleRewards;
 use crate::game_data::*;
 use crate::input;
+use crate::localization;
 
 pub enum BattleResult {
-    Win,
-    Lose,
+    Win(BattleRewards),
+    /// 戦闘不能になったときに最後に行動していた敵
+    Lose(EnemyId),
+    Escaped,
+}
+
+/// 戦闘で出会う敵と時間帯
+pub struct Encounter<'a> {
+    pub enemy_data: Vec<&'a EnemyData>,
+    pub time: BattleTime,
 }
 
 pub(super) struct BattleScene<'a> {
@@ -25,35 +36,170 @@ impl<'a> BattleScene<'a> {
         player_index: usize,
         player_data: &'a PlayerData,
         item_data: &'a Vec<ItemData>,
-        // battle_data: ,
-        time: BattleTime,
+        encounter: &Encounter<'a>,
+        player_state: &game::PlayerState,
     ) -> Self {
-        let view = BattleView::new(cx, time, player_index);
-        let model = BattleModel::new(player_index, player_data, item_data);
+        let view = BattleView::new(cx, encounter.time, player_index, encounter.enemy_data.len());
+        let model = BattleModel::new(
+            player_data,
+            item_data,
+            &encounter.enemy_data,
+            player_state,
+            localization::get_text,
+        );
         Self { cx, view, model }
     }
 
-    pub(crate) async fn start(&mut self, player_state: &mut game::PlayerState) -> BattleResult {
-        self.view.set_monster_image(
-            "/image/monster/monster.png",
-            "/image/monster/monster-shadow.png",
-        );
-        self.view.set_player_hp(150, 150);
-        self.view.set_player_tp(50, 50);
-        self.view.set_enemy_hp(250, 250);
+    async fn process_view_commands(&self, view_commands: Vec<BattleViewCommand>) {
+        // 会心や弱点の表示は直後のダメージ表示にまとめて重ねる
+        let mut overlays = vec![];
+        for command in view_commands.into_iter() {
+            match command {
+                BattleViewCommand::Message { key, args } => {
+                    let args = args.iter().map(|a| a.as_str()).collect::<Vec<_>>();
+                    self.view.set_message(key, &args).await;
+                }
+                BattleViewCommand::PlayerBlink => self.view.player_blink_animation().await,
+                BattleViewCommand::EnemyBlink { index } => {
+                    self.view.enemy_blink_animation(index).await
+                }
+                BattleViewCommand::EnemyDamage {
+                    index,
+                    damage,
+                    hp,
+                    max_hp,
+                } => {
+                    self.view.set_enemy_hp(index, hp, max_hp);
+                    self.cx.play_sfx("/audio/sfx/hit-0.ogg");
+                    let overlays = std::mem::take(&mut overlays);
+                    if self.model.enemy_data(index).boss == Some(true) {
+                        self.view
+                            .enemy_boss_damage_animation(index, damage, &overlays)
+                            .await;
+                    } else {
+                        self.view
+                            .enemy_damage_animation(index, damage, &overlays)
+                            .await;
+                    }
+                }
+                BattleViewCommand::EnemyHeal {
+                    index,
+                    heal,
+                    hp,
+                    max_hp,
+                } => {
+                    self.view.set_enemy_hp(index, hp, max_hp);
+                    self.cx.play_sfx("/audio/sfx/heal.ogg");
+                    self.view.enemy_heal_animation(index, heal).await;
+                }
+                BattleViewCommand::EnemyDown { index } => {
+                    self.cx.play_sfx("/audio/sfx/down.ogg");
+                    let enemy_name = self.model.enemy_name(index);
+                    let args = [enemy_name.as_str()];
+                    if self.model.enemy_data(index).boss == Some(true) {
+                        join!(
+                            self.view.enemy_boss_down_animation(index),
+                            self.view.set_message("battle-message-boss-down", &args),
+                        );
+                    } else {
+                        join!(
+                            self.view.enemy_down_animation(index),
+                            self.view.set_message("battle-message-enemy-down", &args),
+                        );
+                    }
+                }
+                BattleViewCommand::EnemyPhaseChange { index } => {
+                    self.cx.play_sfx(&self.model.enemy_data(index).bark_sfx);
+                    self.view.enemy_blink_animation(index).await;
+                }
+                BattleViewCommand::PlayerDamage { damage, hp, max_hp } => {
+                    self.view.set_player_hp(hp, max_hp);
+                    self.cx.play_sfx("/audio/sfx/hit-1.ogg");
+                    self.view
+                        .player_damage_animation(damage, &std::mem::take(&mut overlays));
+                }
+                BattleViewCommand::PlayerHeal { heal, hp, max_hp } => {
+                    self.view.set_player_hp(hp, max_hp);
+                    self.cx.play_sfx("/audio/sfx/heal.ogg");
+                    self.view.player_heal_animation(heal);
+                }
+                BattleViewCommand::Critical => overlays.push(DamageOverlay::Critical),
+                BattleViewCommand::Weak => overlays.push(DamageOverlay::Weak),
+                BattleViewCommand::PlayerMiss => {
+                    self.cx.play_sfx("/audio/sfx/cancel.ogg");
+                    self.view.player_miss_animation();
+                }
+                BattleViewCommand::EnemyMiss { index } => {
+                    self.cx.play_sfx("/audio/sfx/cancel.ogg");
+                    self.view.enemy_miss_animation(index);
+                }
+                BattleViewCommand::PlayerSetTp { tp, max_tp } => {
+                    self.view.set_player_tp(tp, max_tp);
+                }
+                BattleViewCommand::PlayerModifiers { items } => {
+                    self.view.set_player_modifiers(&items);
+                }
+                BattleViewCommand::EnemyModifiers { index, items } => {
+                    self.view.set_enemy_modifiers(index, &items);
+                }
+                BattleViewCommand::WaitKey => input::wait_select_button(self.cx).await,
+                BattleViewCommand::Delay { millis } => delay(Duration::from_millis(millis)).await,
+            }
+        }
+    }
 
-        self.view.battle_start().await;
-        self.cx.play_sfx("/audio/sfx/monster-bark-0.ogg");
+    pub(crate) fn turn_count(&self) -> u32 {
+        self.model.turn_count()
+    }
+
+    pub(crate) async fn start(
+        &mut self,
+        player_state: &mut game::PlayerState,
+        rng: &mut impl Rng,
+    ) -> BattleResult {
+        let enemy_count = self.model.enemy_count();
+        let mut bosses = vec![];
+        for index in 0..enemy_count {
+            let enemy_data = self.model.enemy_data(index);
+            self.view
+                .set_monster_image(index, &enemy_data.image, &enemy_data.shadow_image);
+            let (hp, max_hp) = self.model.enemy_hp(index);
+            self.view.set_enemy_hp(index, hp, max_hp);
+            bosses.push(enemy_data.boss == Some(true));
+        }
+        let (hp, max_hp) = self.model.player_hp();
+        self.view.set_player_hp(hp, max_hp);
+        let (tp, max_tp) = self.model.player_tp();
+        self.view.set_player_tp(tp, max_tp);
+
+        self.view.battle_start(&bosses).await;
+        self.cx.play_sfx(&self.model.enemy_data(0).bark_sfx);
 
         self.view.set_turn_number(1);
-        self.view
-            .set_message("battle-message-battle-start", &["森林チョウ"])
-            .await;
+        let leader_name = localization::get_text(&self.model.enemy_data(0).enemy_name);
+        if enemy_count > 1 {
+            self.view
+                .set_message("battle-message-battle-start-group", &[&leader_name])
+                .await;
+        } else {
+            self.view
+                .set_message("battle-message-battle-start", &[&leader_name])
+                .await;
+        }
         input::wait_select_button(self.cx).await;
 
         let result = loop {
-            let turn = self.model.turn_start();
-            self.view.set_turn_number(turn);
+            let (view_commands, turn_result) = self.model.turn_start(player_state, rng);
+            self.view.set_turn_number(self.model.turn_count());
+            self.process_view_commands(view_commands).await;
+            match turn_result {
+                BattleTurnResult::Win => {
+                    break BattleResult::Win(self.model.roll_rewards(player_state, rng))
+                }
+                BattleTurnResult::Lose => break BattleResult::Lose(self.model.killed_by()),
+                BattleTurnResult::Escaped => break BattleResult::Escaped,
+                BattleTurnResult::Continue => (),
+            }
 
             self.view
                 .set_message("battle-message-turn-start", &[])
@@ -62,58 +208,20 @@ impl<'a> BattleScene<'a> {
             let select_command_data = self.model.select_command_data(player_state);
             let command = self.view.select_command(select_command_data).await;
 
-            let (view_commands, turn_result) = self.model.process_turn(player_state, command);
-            for command in view_commands.into_iter() {
-                match command {
-                    BattleViewCommand::Message { key, args } => {
-                        self.view.set_message(key, args).await;
-                    }
-                    BattleViewCommand::PlayerBlink => self.view.player_blink_animation().await,
-                    BattleViewCommand::EnemyBlink => self.view.enemy_blink_animation().await,
-                    BattleViewCommand::EnemyDamage { damage, hp, max_hp } => {
-                        self.view.set_enemy_hp(hp, max_hp);
-                        self.cx.play_sfx("/audio/sfx/hit-0.ogg");
-                        self.view.enemy_damage_animation(damage).await;
-                    }
-                    BattleViewCommand::EnemyHeal { heal, hp, max_hp } => {
-                        self.view.set_enemy_hp(hp, max_hp);
-                        self.cx.play_sfx("/audio/sfx/heal.ogg");
-                        self.view.enemy_heal_animation(heal).await;
-                    }
-                    BattleViewCommand::EnemyDown => {
-                        self.cx.play_sfx("/audio/sfx/down.ogg");
-                        join!(
-                            self.view.enemy_down_animation(),
-                            self.view
-                                .set_message("battle-message-enemy-down", &["森林チョウ"]),
-                        );
-                    }
-                    BattleViewCommand::PlayerDamage { damage, hp, max_hp } => {
-                        self.view.set_player_hp(hp, max_hp);
-                        self.cx.play_sfx("/audio/sfx/hit-1.ogg");
-                        self.view.player_damage_animation(damage);
-                    }
-                    BattleViewCommand::PlayerHeal { heal, hp, max_hp } => {
-                        self.view.set_player_hp(hp, max_hp);
-                        self.cx.play_sfx("/audio/sfx/heal.ogg");
-                        self.view.player_heal_animation(heal);
-                    }
-                    BattleViewCommand::PlayerSetTp { tp, max_tp } => {
-                        self.view.set_player_tp(tp, max_tp);
-                    }
-                    BattleViewCommand::WaitKey => input::wait_select_button(self.cx).await,
-                    BattleViewCommand::Delay { millis } => {
-                        delay(Duration::from_millis(millis)).await
-                    }
-                }
-            }
+            let (view_commands, turn_result) = self.model.process_turn(player_state, command, rng);
+            self.process_view_commands(view_commands).await;
             match turn_result {
-                BattleTurnResult::Win => break BattleResult::Win,
-                BattleTurnResult::Lose => break BattleResult::Lose,
+                BattleTurnResult::Win => {
+                    break BattleResult::Win(self.model.roll_rewards(player_state, rng))
+                }
+                BattleTurnResult::Lose => break BattleResult::Lose(self.model.killed_by()),
+                BattleTurnResult::Escaped => break BattleResult::Escaped,
                 BattleTurnResult::Continue => continue,
             }
         };
 
+        self.model.write_back_player_state(player_state);
+
         self.view.battle_end().await;
         result
     }
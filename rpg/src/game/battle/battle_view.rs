@@ -1,5 +1,6 @@
 use animation_engine::executor::*;
 use animation_engine::*;
+use futures::future::join_all;
 use futures::{join, select, FutureExt};
 use std::time::Duration;
 
@@ -7,9 +8,11 @@ mod background_view;
 mod cover_view;
 mod damage_number_view;
 mod enemy_view;
+mod info_window;
 mod items_window;
 mod menu_view;
 mod message_window_view;
+mod modifier_list_view;
 mod number_view;
 mod player_view;
 mod skills_window;
@@ -18,32 +21,40 @@ use crate::game::battle::battle_model::*;
 use crate::game::battle::battle_view::background_view::*;
 use crate::game::battle::battle_view::cover_view::*;
 use crate::game::battle::battle_view::enemy_view::*;
+use crate::game::battle::battle_view::info_window::*;
 use crate::game::battle::battle_view::items_window::*;
 use crate::game::battle::battle_view::menu_view::*;
 use crate::game::battle::battle_view::message_window_view::*;
+use crate::game::battle::battle_view::modifier_list_view::*;
 use crate::game::battle::battle_view::player_view::*;
 use crate::game::battle::battle_view::skills_window::*;
 use crate::game_data;
 use crate::game_data::*;
 use crate::input;
 
+pub(super) use damage_number_view::DamageOverlay;
+pub(super) use info_window::InfoWindowData;
 pub(super) use items_window::ItemWindowItem;
 pub(super) use number_view::Number;
 pub(super) use number_view::NumberView;
 pub(super) use skills_window::SkillWindowItem;
 
-// pub(super) struct PlayerModifierViewItem<'a> {
-//     name_key: String,
-//     turns: u32,
-//     description_key: String,
-//     description_args: &'a [&'a str],
-// }
-// pub(super) struct EnemyModifierViewItem<'a> {
-//     name_key: String,
-//     turns: u32,
-//     description_key: String,
-//     description_args: &'a [&'a str],
-// }
+pub(super) struct ModifierViewItem {
+    pub name_key: String,
+    pub turns: u32,
+    pub description_key: String,
+    pub description_args: Vec<String>,
+}
+
+/// 敵の数ごとの(x座標, 拡大率)。MAX_ENEMIESまで
+fn enemy_layout(count: usize) -> &'static [(f32, f32)] {
+    match count {
+        1 => &[(235.0, 1.0)],
+        2 => &[(40.0, 0.8), (430.0, 0.8)],
+        3 => &[(-40.0, 0.65), (235.0, 0.65), (510.0, 0.65)],
+        _ => panic!("Unsupported enemy count: {}", count),
+    }
+}
 
 pub(super) struct BattleView<'a> {
     cx: &'a AnimationEngineContext,
@@ -51,61 +62,90 @@ pub(super) struct BattleView<'a> {
     cover: CoverView<'a>,
     message_window: MessageWindowView<'a>,
     player: PlayerView<'a>,
-    enemy: EnemyView<'a>,
+    enemies: Vec<EnemyView<'a>>,
+    player_modifiers: ModifierListView<'a>,
+    enemy_modifiers: Vec<ModifierListView<'a>>,
     menu: MenuView<'a>,
     skills: SkillsWindow<'a>,
     items: ItemsWindow<'a>,
+    info: InfoWindow<'a>,
 }
 impl<'a> BattleView<'a> {
     pub(super) fn new(
         cx: &'a AnimationEngineContext,
         time: game_data::BattleTime,
         player_index: usize,
+        enemy_count: usize,
     ) -> Self {
         let background = BackgroundView::new(cx, time);
         let cover = CoverView::new(cx);
         let message_window = MessageWindowView::new(cx);
         let player = PlayerView::new(cx, player_index);
-        let enemy = EnemyView::new(cx);
+        let layout = enemy_layout(enemy_count);
+        let enemies = layout
+            .iter()
+            .map(|&(x, scale)| EnemyView::new(cx, x, scale))
+            .collect();
+        let player_modifiers = ModifierListView::new(cx, 1070.0, 440.0, -36.0);
+        let enemy_modifiers = layout
+            .iter()
+            .map(|&(x, scale)| ModifierListView::new(cx, x + 135.0 * scale, 200.0, 36.0))
+            .collect();
         let menu = MenuView::new(cx);
         let skills = SkillsWindow::new(cx);
         let items = ItemsWindow::new(cx);
+        let info = InfoWindow::new(cx);
         Self {
             cx,
             background,
             cover,
             message_window,
             player,
-            enemy,
+            enemies,
+            player_modifiers,
+            enemy_modifiers,
             menu,
             skills,
             items,
+            info,
         }
     }
 
     pub(super) fn set_monster_image(
         &self,
+        index: usize,
         image_key: impl ToString,
         image_shadow_key: impl ToString,
     ) {
-        self.enemy.set_monster_image(image_key, image_shadow_key);
+        self.enemies[index].set_monster_image(image_key, image_shadow_key);
     }
 
-    pub(super) async fn battle_start(&self) {
+    pub(super) async fn battle_start(&self, bosses: &[bool]) {
         join!(
             self.background.start(),
             self.cover.start_battle(),
             self.message_window.start_battle(),
             self.player.start_battle(),
-            self.enemy.start_battle(),
+            join_all(
+                self.enemies
+                    .iter()
+                    .zip(bosses)
+                    .map(|(enemy, &boss)| async move {
+                        if boss {
+                            enemy.start_battle_boss().await;
+                        } else {
+                            enemy.start_battle().await;
+                        }
+                    })
+            ),
         );
     }
     pub(super) async fn battle_end(&self) {
         self.cover.fade_out().await;
     }
 
-    pub(super) fn set_enemy_hp(&self, hp: i32, max_hp: i32) {
-        self.enemy.set_hp(hp, max_hp);
+    pub(super) fn set_enemy_hp(&self, index: usize, hp: i32, max_hp: i32) {
+        self.enemies[index].set_hp(hp, max_hp);
     }
     pub(super) fn set_player_hp(&self, hp: i32, max_hp: i32) {
         self.player.set_hp(hp, max_hp);
@@ -113,29 +153,53 @@ impl<'a> BattleView<'a> {
     pub(super) fn set_player_tp(&self, tp: i32, max_tp: i32) {
         self.player.set_tp(tp, max_tp);
     }
+    pub(super) fn set_player_modifiers(&self, items: &[ModifierViewItem]) {
+        self.player_modifiers.set_items(items);
+    }
+    pub(super) fn set_enemy_modifiers(&self, index: usize, items: &[ModifierViewItem]) {
+        self.enemy_modifiers[index].set_items(items);
+    }
 
-    // pub(super) async fn enemy_boss_damage_animation(&self, damage: i32) {
-    //     self.enemy.damage_animation(damage);
-    // }
-    pub(super) async fn enemy_damage_animation(&self, damage: i32) {
-        self.enemy.damage_animation(damage);
+    pub(super) async fn enemy_boss_damage_animation(
+        &self,
+        index: usize,
+        damage: i32,
+        overlays: &[DamageOverlay],
+    ) {
+        self.enemies[index].boss_damage_animation(damage, overlays);
+    }
+    pub(super) async fn enemy_damage_animation(
+        &self,
+        index: usize,
+        damage: i32,
+        overlays: &[DamageOverlay],
+    ) {
+        self.enemies[index].damage_animation(damage, overlays);
+    }
+    pub(super) fn enemy_miss_animation(&self, index: usize) {
+        self.enemies[index].miss_animation();
+    }
+    pub(super) async fn enemy_heal_animation(&self, index: usize, heal: i32) {
+        self.enemies[index].heal_animation(heal);
     }
-    pub(super) async fn enemy_heal_animation(&self, heal: i32) {
-        self.enemy.heal_animation(heal);
+    pub(super) fn player_damage_animation(&self, damage: i32, overlays: &[DamageOverlay]) {
+        self.player.damage_animation(damage, overlays);
     }
-    pub(super) fn player_damage_animation(&self, damage: i32) {
-        self.player.damage_animation(damage);
+    pub(super) fn player_miss_animation(&self) {
+        self.player.miss_animation();
     }
     pub(super) fn player_heal_animation(&self, heal: i32) {
         self.player.heal_animation(heal);
     }
 
-    pub(super) async fn enemy_down_animation(&self) {
-        self.enemy.down_enemy().await;
+    pub(super) async fn enemy_down_animation(&self, index: usize) {
+        self.enemy_modifiers[index].set_items(&[]);
+        self.enemies[index].down_enemy().await;
+    }
+    pub(super) async fn enemy_boss_down_animation(&self, index: usize) {
+        self.enemy_modifiers[index].set_items(&[]);
+        self.enemies[index].down_enemy_boss().await;
     }
-    // pub(super) async fn enemy_boss_down_animation(&self) {
-    //     self.enemy.down_enemy().await;
-    // }
 
     pub(super) async fn set_message(&self, message_key: impl ToString, message_args: &[&str]) {
         self.message_window
@@ -147,14 +211,16 @@ impl<'a> BattleView<'a> {
         self.message_window.set_turns(turn);
     }
 
-    pub(super) async fn enemy_blink_animation(&self) {
-        self.enemy.blink_animation().await;
+    pub(super) async fn enemy_blink_animation(&self, index: usize) {
+        self.enemies[index].blink_animation().await;
     }
-    pub(super) async fn enemy_blink_animation_loop(&self) {
-        self.enemy.blink_animation_loop().await;
+    pub(super) async fn enemy_blink_animation_loop(&self, index: usize) {
+        self.enemies[index].blink_animation_loop().await;
     }
     pub(super) fn reset_enemy_blink(&self) {
-        self.enemy.reset_blink();
+        for enemy in self.enemies.iter() {
+            enemy.reset_blink();
+        }
     }
     pub(super) async fn player_blink_animation(&self) {
         self.player.blink_animation().await;
@@ -179,6 +245,52 @@ impl<'a> BattleView<'a> {
         self.menu.hide().await;
     }
 
+    /// 対象の敵を左右で選ぶ。キャンセルされた場合はNone
+    async fn select_enemy_target(&self, enemy_alive: &[bool]) -> Option<usize> {
+        let targets = (0..enemy_alive.len())
+            .filter(|&i| enemy_alive[i])
+            .collect::<Vec<_>>();
+        let mut cursor = 0;
+        loop {
+            select! {
+                _ = self.enemy_blink_animation_loop(targets[cursor]).fuse() => unreachable!(),
+                _ = input::wait_select_button(self.cx).fuse() => {
+                    self.cx.play_sfx("/audio/sfx/select.ogg");
+                    return Some(targets[cursor]);
+                }
+                _ = input::wait_cancel_button(self.cx).fuse() => {
+                    self.cx.play_sfx("/audio/sfx/cancel.ogg");
+                    self.reset_player_blink();
+                    self.reset_enemy_blink();
+                    return None;
+                }
+                _ = input::wait_left(self.cx).fuse() => {
+                    cursor = (cursor + targets.len() - 1) % targets.len();
+                    self.cx.play_sfx("/audio/sfx/cursor.ogg");
+                }
+                _ = input::wait_right(self.cx).fuse() => {
+                    cursor = (cursor + 1) % targets.len();
+                    self.cx.play_sfx("/audio/sfx/cursor.ogg");
+                }
+            }
+            self.reset_enemy_blink();
+            delay(Duration::from_millis(150)).await;
+        }
+    }
+
+    async fn open_enemy_info(&self, enemy_info: &[InfoWindowData], enemy_alive: &[bool]) {
+        let alive_count = enemy_alive.iter().filter(|&&alive| alive).count();
+        let target = if alive_count == 1 {
+            enemy_alive.iter().position(|&alive| alive)
+        } else {
+            self.select_enemy_target(enemy_alive).await
+        };
+        self.reset_enemy_blink();
+        if let Some(index) = target {
+            self.info.open(&enemy_info[index]).await;
+        }
+    }
+
     pub(super) async fn select_command<'b>(
         &mut self,
         data: SelectCommandData<'b>,
@@ -188,7 +300,11 @@ impl<'a> BattleView<'a> {
             skill_data,
             items,
             item_data,
+            player_info,
+            enemy_info,
+            enemy_alive,
         } = data;
+        let first_alive = enemy_alive.iter().position(|&alive| alive).unwrap();
 
         let mut index = 0;
         self.set_menu_cursor(index);
@@ -199,17 +315,29 @@ impl<'a> BattleView<'a> {
             loop {
                 select! {
                     _ = input::wait_down(self.cx).fuse() => {
-                        index = (index + 1) % 5;
+                        index = (index + 1) % 6;
                         self.cx.play_sfx("/audio/sfx/cursor.ogg");
                     }
                     _ = input::wait_up(self.cx).fuse() => {
-                        index = (index + 5 - 1) % 5;
+                        index = (index + 6 - 1) % 6;
                         self.cx.play_sfx("/audio/sfx/cursor.ogg");
                     }
                     _ = input::wait_select_button(self.cx).fuse() => {
                         self.cx.play_sfx("/audio/sfx/select.ogg");
                         break;
                     }
+                    _ = input::wait_left_trigger(self.cx).fuse() => {
+                        self.cx.play_sfx("/audio/sfx/select.ogg");
+                        self.set_menu_active(false);
+                        self.open_enemy_info(&enemy_info, &enemy_alive).await;
+                        self.set_menu_active(true);
+                    }
+                    _ = input::wait_right_trigger(self.cx).fuse() => {
+                        self.cx.play_sfx("/audio/sfx/select.ogg");
+                        self.set_menu_active(false);
+                        self.info.open(&player_info).await;
+                        self.set_menu_active(true);
+                    }
                 }
                 self.set_menu_cursor(index);
                 delay(Duration::from_millis(150)).await;
@@ -220,40 +348,22 @@ impl<'a> BattleView<'a> {
 
             match index {
                 0 => {
-                    select! {
-                        _ = self.enemy_blink_animation_loop().fuse() => unreachable!(),
-                        _ = input::wait_select_button(self.cx).fuse() => {
-                            self.cx.play_sfx("/audio/sfx/select.ogg");
-                            break 'select_command BattleCommand::Skill(SkillId(0));
-                        }
-                        _ = input::wait_cancel_button(self.cx).fuse() => {
-                            self.cx.play_sfx("/audio/sfx/cancel.ogg");
-                            self.reset_player_blink();
-                            self.reset_enemy_blink();
-                        }
+                    if let Some(target) = self.select_enemy_target(&enemy_alive).await {
+                        break 'select_command BattleCommand::Skill(SkillId(0), target);
                     }
                 }
                 1 => loop {
                     if let Some(skill) = self.skills.select_skill(&skills, &skill_data).await {
                         if skill.skill_target == SkillTarget::Enemy {
-                            select! {
-                                _ = self.enemy_blink_animation_loop().fuse() => unreachable!(),
-                                _ = input::wait_select_button(self.cx).fuse() => {
-                                    self.cx.play_sfx("/audio/sfx/select.ogg");
-                                    break 'select_command BattleCommand::Skill(skill.id);
-                                }
-                                _ = input::wait_cancel_button(self.cx).fuse() => {
-                                    self.cx.play_sfx("/audio/sfx/cancel.ogg");
-                                    self.reset_player_blink();
-                                    self.reset_enemy_blink();
-                                }
+                            if let Some(target) = self.select_enemy_target(&enemy_alive).await {
+                                break 'select_command BattleCommand::Skill(skill.id, target);
                             }
                         } else {
                             select! {
                                 _ = self.player_blink_animation_loop().fuse() => unreachable!(),
                                 _ = input::wait_select_button(self.cx).fuse() => {
                                     self.cx.play_sfx("/audio/sfx/select.ogg");
-                                    break 'select_command BattleCommand::Skill(skill.id);
+                                    break 'select_command BattleCommand::Skill(skill.id, first_alive);
                                 }
                                 _ = input::wait_cancel_button(self.cx).fuse() => {
                                     self.cx.play_sfx("/audio/sfx/cancel.ogg");
@@ -269,24 +379,15 @@ impl<'a> BattleView<'a> {
                 2 => loop {
                     if let Some(item) = self.items.select_item(&items, &item_data).await {
                         if item.item_target == ItemTarget::Enemy {
-                            select! {
-                                _ = self.enemy_blink_animation_loop().fuse() => unreachable!(),
-                                _ = input::wait_select_button(self.cx).fuse() => {
-                                    self.cx.play_sfx("/audio/sfx/select.ogg");
-                                    break 'select_command BattleCommand::Item(item.id);
-                                }
-                                _ = input::wait_cancel_button(self.cx).fuse() => {
-                                    self.cx.play_sfx("/audio/sfx/cancel.ogg");
-                                    self.reset_player_blink();
-                                    self.reset_enemy_blink();
-                                }
+                            if let Some(target) = self.select_enemy_target(&enemy_alive).await {
+                                break 'select_command BattleCommand::Item(item.id, target);
                             }
                         } else {
                             select! {
                                 _ = self.player_blink_animation_loop().fuse() => unreachable!(),
                                 _ = input::wait_select_button(self.cx).fuse() => {
                                     self.cx.play_sfx("/audio/sfx/select.ogg");
-                                    break 'select_command BattleCommand::Item(item.id);
+                                    break 'select_command BattleCommand::Item(item.id, first_alive);
                                 }
                                 _ = input::wait_cancel_button(self.cx).fuse() => {
                                     self.cx.play_sfx("/audio/sfx/cancel.ogg");
@@ -299,12 +400,9 @@ impl<'a> BattleView<'a> {
                         break;
                     }
                 },
-                3 => {
-                    self.cx.play_sfx("/audio/sfx/select.ogg");
-                }
-                4 => {
-                    self.cx.play_sfx("/audio/sfx/select.ogg");
-                }
+                3 => self.info.open(&player_info).await,
+                4 => self.open_enemy_info(&enemy_info, &enemy_alive).await,
+                5 => break 'select_command BattleCommand::Escape,
                 _ => unreachable!(),
             }
         };
@@ -1,6 +1,30 @@
 use animation_engine::*;
 use futures::future::try_join_all;
 
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(in super::super) enum DamageOverlay {
+    Critical,
+    Miss,
+    Weak,
+}
+impl DamageOverlay {
+    fn text_key(&self) -> &'static str {
+        match self {
+            DamageOverlay::Critical => "battle-overlay-critical",
+            DamageOverlay::Miss => "battle-overlay-miss",
+            DamageOverlay::Weak => "battle-overlay-weak",
+        }
+    }
+
+    fn color(&self) -> (f32, f32, f32) {
+        match self {
+            DamageOverlay::Critical => (1.0, 0.85, 0.2),
+            DamageOverlay::Miss => (0.8, 0.8, 0.8),
+            DamageOverlay::Weak => (1.0, 0.4, 0.3),
+        }
+    }
+}
+
 pub(super) struct DamageNumberView<'a> {
     cx: &'a AnimationEngineContext,
     numbers: Vec<Entity>,
@@ -13,7 +37,17 @@ impl<'a> DamageNumberView<'a> {
         y: f32,
         z: u32,
     ) -> Self {
-        Self::new(cx, number, x, y, z, "damage-")
+        Self::new(cx, number, x, y, z, "damage-", 1.0)
+    }
+    /// 会心の一撃は数字を大きく表示する
+    pub(super) fn new_critical(
+        cx: &'a AnimationEngineContext,
+        number: i32,
+        x: f32,
+        y: f32,
+        z: u32,
+    ) -> Self {
+        Self::new(cx, number, x, y, z, "damage-", 1.3)
     }
     pub(super) fn new_heal(
         cx: &'a AnimationEngineContext,
@@ -22,7 +56,7 @@ impl<'a> DamageNumberView<'a> {
         y: f32,
         z: u32,
     ) -> Self {
-        Self::new(cx, number, x, y, z, "heal-")
+        Self::new(cx, number, x, y, z, "heal-", 1.0)
     }
 
     fn new(
@@ -32,6 +66,7 @@ impl<'a> DamageNumberView<'a> {
         y: f32,
         z: u32,
         prefix: &str,
+        scale: f32,
     ) -> Self {
         let mut numbers = vec![];
         let mut n = number;
@@ -40,6 +75,7 @@ impl<'a> DamageNumberView<'a> {
 
             let number_entity = cx.add_image(AddImageInfo {
                 name: format!("/image/number/{}{}.png", prefix, digit),
+                scale,
                 ..Default::default()
             });
             numbers.push(number_entity);
@@ -52,7 +88,7 @@ impl<'a> DamageNumberView<'a> {
 
         let len = numbers.len();
         for (i, &number_entity) in numbers.iter().enumerate() {
-            let x = x + i as f32 * -20.0 + len as f32 * 0.5 * 20.0;
+            let x = x + (i as f32 * -20.0 + len as f32 * 0.5 * 20.0) * scale;
             let y = y - 18.0;
             cx.set_position(number_entity, x, y, z).unwrap();
         }
@@ -77,3 +113,45 @@ impl<'a> Drop for DamageNumberView<'a> {
         }
     }
 }
+
+/// ダメージの数字の上に重ねて表示する「Critical!」などの文字
+pub(super) struct DamageOverlayView<'a> {
+    cx: &'a AnimationEngineContext,
+    text: Entity,
+}
+impl<'a> DamageOverlayView<'a> {
+    pub(super) fn new(
+        cx: &'a AnimationEngineContext,
+        overlay: DamageOverlay,
+        x: f32,
+        y: f32,
+        z: u32,
+    ) -> Self {
+        let (r, g, b) = overlay.color();
+        let text = cx.add_text(AddTextInfo {
+            key: overlay.text_key().into(),
+            font_size: 40.0,
+            x: x - 60.0,
+            y,
+            z,
+            r,
+            g,
+            b,
+            a: 0.0,
+            ..Default::default()
+        });
+        Self { cx, text }
+    }
+
+    pub(super) async fn start_animation(&self, animation: &str) {
+        self.cx
+            .play_animation(self.text, animation)
+            .await
+            .expect("animation not found");
+    }
+}
+impl<'a> Drop for DamageOverlayView<'a> {
+    fn drop(&mut self) {
+        self.cx.delete_entity(self.text);
+    }
+}
@@ -1,11 +1,41 @@
 use animation_engine::executor::*;
 use animation_engine::*;
+use futures::future::join_all;
 use futures::{join, try_join};
 
 use crate::game::battle::battle_view::damage_number_view::*;
 
+const DAMAGE_X_KEYS: &[(u32, f32)] = &[(1, 0.0), (5, 20.0), (10, -20.0), (20, 0.0)];
+const DAMAGE_Y_KEYS: &[(u32, f32)] = &[(1, 140.0), (15, 160.0), (20, 140.0)];
+const BOSS_DAMAGE_X_KEYS: &[(u32, f32)] = &[
+    (1, 0.0),
+    (4, 12.0),
+    (8, -12.0),
+    (12, 8.0),
+    (16, -8.0),
+    (22, 3.0),
+    (30, 0.0),
+];
+const BOSS_DAMAGE_Y_KEYS: &[(u32, f32)] = &[(1, 140.0), (15, 148.0), (30, 140.0)];
+
+fn interpolate_keys(keys: &[(u32, f32)], frame: u32) -> f32 {
+    let next = keys
+        .iter()
+        .position(|&(f, _)| f >= frame)
+        .unwrap_or(keys.len() - 1);
+    if next == 0 {
+        return keys[0].1;
+    }
+    let (f0, v0) = keys[next - 1];
+    let (f1, v1) = keys[next];
+    let t = (frame.min(f1) - f0) as f32 / (f1 - f0) as f32;
+    v0 + (v1 - v0) * t
+}
+
 pub(super) struct EnemyView<'a> {
     cx: &'a AnimationEngineContext,
+    x: f32,
+    scale: f32,
     enemy_image: Entity,
     enemy_shadow_image: Entity,
     part_5: Entity,
@@ -13,34 +43,39 @@ pub(super) struct EnemyView<'a> {
     hp_bar: Entity,
 }
 impl<'a> EnemyView<'a> {
-    pub(super) fn new(cx: &'a AnimationEngineContext) -> Self {
+    pub(super) fn new(cx: &'a AnimationEngineContext, x: f32, scale: f32) -> Self {
         let enemy_image = cx.add_image(AddImageInfo {
-            x: 235.0,
+            x,
             z: 340,
+            scale,
             ..Default::default()
         });
         let enemy_shadow_image = cx.add_image(AddImageInfo {
-            x: 235.0,
+            x,
             z: 335,
+            scale,
             ..Default::default()
         });
         let part_5 = cx.add_image(AddImageInfo {
             name: "/image/ui/battle-part-5.png".into(),
-            x: 346.0,
+            x: x + 111.0 * scale,
             z: 355,
+            scale,
             ..Default::default()
         });
         let part_6 = cx.add_image(AddImageInfo {
             name: "/image/ui/battle-part-6.png".into(),
-            x: 346.0,
+            x: x + 111.0 * scale,
             z: 360,
+            scale,
             ..Default::default()
         });
         let hp_bar = cx.add_rect(AddRectInfo {
             width: 285.0,
             height: 35.0,
-            x: 345.0,
+            x: x + 110.0 * scale,
             z: 357,
+            scale,
             r: 1.0,
             g: 0.0,
             b: 70.0 / 255.0,
@@ -49,6 +84,8 @@ impl<'a> EnemyView<'a> {
         });
         Self {
             cx,
+            x,
+            scale,
             enemy_image,
             enemy_shadow_image,
             part_5,
@@ -104,7 +141,47 @@ impl<'a> EnemyView<'a> {
         .expect("animation not found");
     }
 
-    // pub(super) async fn start_battle_boss(&self)
+    pub(super) async fn start_battle_boss(&self) {
+        try_join!(
+            self.cx.play_animation(
+                self.enemy_image,
+                "/animation/battle/enemy-image-battle-start-boss.yml"
+            ),
+            self.cx.play_animation(
+                self.enemy_shadow_image,
+                "/animation/battle/enemy-shadow-image-battle-start-boss.yml"
+            ),
+            self.cx
+                .play_animation(self.part_5, "/animation/battle/enemy-hp-battle-start.yml"),
+            self.cx
+                .play_animation(self.part_6, "/animation/battle/enemy-hp-battle-start.yml"),
+            self.cx.play_animation(
+                self.hp_bar,
+                "/animation/battle/enemy-hp-bar-battle-start.yml"
+            ),
+        )
+        .expect("animation not found");
+    }
+
+    pub(super) async fn down_enemy_boss(&self) {
+        try_join!(
+            self.cx.play_animation(
+                self.enemy_image,
+                "/animation/battle/enemy-image-down-boss.yml"
+            ),
+            self.cx.play_animation(
+                self.enemy_shadow_image,
+                "/animation/battle/enemy-shadow-image-down-boss.yml"
+            ),
+            self.cx
+                .play_animation(self.part_5, "/animation/battle/enemy-hp-down.yml"),
+            self.cx
+                .play_animation(self.part_6, "/animation/battle/enemy-hp-down.yml"),
+            self.cx
+                .play_animation(self.hp_bar, "/animation/battle/enemy-hp-bar-down.yml"),
+        )
+        .expect("animation not found");
+    }
 
     pub(super) fn set_hp(&self, hp: i32, max_hp: i32) {
         self.cx
@@ -115,26 +192,83 @@ impl<'a> EnemyView<'a> {
             .unwrap();
     }
 
-    pub(super) fn damage_animation(&self, damage: i32) {
+    /// ダメージ時の揺れは敵の配置によって基準位置が変わるため、アニメーションファイルではなくコードで動かす
+    fn shake_animation(&self, x_keys: &'static [(u32, f32)], y_keys: &'static [(u32, f32)]) {
         let cx = self.cx.clone();
+        let x = self.x;
         let enemy_image = self.enemy_image;
         let enemy_shadow_image = self.enemy_shadow_image;
+        let len = x_keys.last().unwrap().0.max(y_keys.last().unwrap().0);
         spawn(async move {
-            let damage = DamageNumberView::new_damage(&cx, damage, 500.0, 460.0, 370);
-            let result = join!(
-                cx.play_animation(enemy_image, "/animation/battle/enemy-damage.yml"),
-                cx.play_animation(enemy_shadow_image, "/animation/battle/enemy-damage.yml"),
-                damage.start_animation("/animation/battle/enemy-damage-number-animation.yml")
+            for frame in 1..=len {
+                let dx = interpolate_keys(x_keys, frame);
+                let y = interpolate_keys(y_keys, frame);
+                cx.set_position(enemy_image, x + dx, y, 340).unwrap();
+                cx.set_position(enemy_shadow_image, x + dx, y, 335).unwrap();
+                next_frame().await;
+            }
+        });
+    }
+
+    fn damage_number_x(&self) -> f32 {
+        self.x + 265.0 * self.scale
+    }
+
+    fn damage_number_animation(&self, damage: i32, overlays: &[DamageOverlay]) {
+        let cx = self.cx.clone();
+        let x = self.damage_number_x();
+        let overlays = overlays.to_vec();
+        spawn(async move {
+            let number = if overlays.contains(&DamageOverlay::Critical) {
+                DamageNumberView::new_critical(&cx, damage, x, 460.0, 370)
+            } else {
+                DamageNumberView::new_damage(&cx, damage, x, 460.0, 370)
+            };
+            let overlays = overlays
+                .iter()
+                .map(|&overlay| DamageOverlayView::new(&cx, overlay, x, 410.0, 375))
+                .collect::<Vec<_>>();
+            join!(
+                number.start_animation("/animation/battle/enemy-damage-number-animation.yml"),
+                join_all(
+                    overlays
+                        .iter()
+                        .enumerate()
+                        .map(|(slot, overlay)| async move {
+                            let animation =
+                                format!("/animation/battle/enemy-overlay-animation-{}.yml", slot);
+                            overlay.start_animation(&animation).await
+                        })
+                ),
             );
-            result.0.expect("animation not found");
-            result.1.expect("animation not found");
+        });
+    }
+
+    pub(super) fn damage_animation(&self, damage: i32, overlays: &[DamageOverlay]) {
+        self.shake_animation(DAMAGE_X_KEYS, DAMAGE_Y_KEYS);
+        self.damage_number_animation(damage, overlays);
+    }
+
+    pub(super) fn boss_damage_animation(&self, damage: i32, overlays: &[DamageOverlay]) {
+        self.shake_animation(BOSS_DAMAGE_X_KEYS, BOSS_DAMAGE_Y_KEYS);
+        self.damage_number_animation(damage, overlays);
+    }
+
+    pub(super) fn miss_animation(&self) {
+        let cx = self.cx.clone();
+        let x = self.damage_number_x();
+        spawn(async move {
+            DamageOverlayView::new(&cx, DamageOverlay::Miss, x, 410.0, 375)
+                .start_animation("/animation/battle/enemy-overlay-animation-0.yml")
+                .await;
         });
     }
 
     pub(super) fn heal_animation(&self, heal: i32) {
         let cx = self.cx.clone();
+        let x = self.damage_number_x();
         spawn(async move {
-            DamageNumberView::new_heal(&cx, heal, 500.0, 460.0, 370)
+            DamageNumberView::new_heal(&cx, heal, x, 460.0, 370)
                 .start_animation("/animation/battle/enemy-damage-number-animation.yml")
                 .await;
         });
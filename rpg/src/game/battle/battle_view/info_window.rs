@@ -0,0 +1,228 @@
+use animation_engine::*;
+use futures::future::try_join_all;
+use futures::{select, FutureExt};
+
+use crate::game::battle::battle_view::*;
+use crate::input;
+use crate::localization;
+
+/// 表示できる行数。あふれたモディファイアは最後の行に件数だけ表示する
+const MAX_LINES: usize = 11;
+
+pub(in super::super) struct InfoWindowData {
+    pub title_key: String,
+    pub lines: Vec<(String, Vec<String>)>,
+    pub description_key: String,
+    pub modifiers: Vec<ModifierViewItem>,
+}
+
+pub(super) struct InfoWindow<'a> {
+    cx: &'a AnimationEngineContext,
+    cover: Entity,
+    part_9: Entity,
+    part_11: Entity,
+    description: Entity,
+    modifier_description: Entity,
+    line_entities: Vec<Entity>,
+}
+impl<'a> InfoWindow<'a> {
+    pub(super) fn new(cx: &'a AnimationEngineContext) -> Self {
+        let cover = cx.add_rect(AddRectInfo {
+            width: 1280.0,
+            height: 720.0,
+            r: 0.0,
+            g: 0.0,
+            b: 0.0,
+            a: 0.0,
+            z: 500,
+            ..Default::default()
+        });
+        let part_9 = cx.add_image(AddImageInfo {
+            name: "/image/ui/battle-part-9.png".into(),
+            x: 60.0,
+            y: 128.0,
+            z: 505,
+            a: 0.0,
+            ..Default::default()
+        });
+        let part_11 = cx.add_image(AddImageInfo {
+            name: "/image/ui/battle-part-11.png".into(),
+            x: 512.0,
+            y: 68.5,
+            z: 505,
+            a: 0.0,
+            ..Default::default()
+        });
+        let description = cx.add_text(AddTextInfo {
+            font_size: 24.0,
+            x: 575.0,
+            y: 150.0,
+            z: 510,
+            a: 0.0,
+            rotation: -0.0872665,
+            ..Default::default()
+        });
+        let modifier_description = cx.add_text(AddTextInfo {
+            key: "battle-info-modifier-descriptions".into(),
+            font_size: 24.0,
+            x: 575.0 - 250.0 * 0.0872665,
+            y: 400.0,
+            z: 510,
+            a: 0.0,
+            rotation: -0.0872665,
+            ..Default::default()
+        });
+        let mut line_entities = vec![];
+        for i in 0..MAX_LINES {
+            let line = cx.add_text(AddTextInfo {
+                font_size: 24.0,
+                x: 120.0 - 44.0 * 0.0872665 * i as f32,
+                y: 194.0 + 44.0 * i as f32,
+                z: 510,
+                a: 0.0,
+                rotation: -0.0872665,
+                ..Default::default()
+            });
+            line_entities.push(line);
+        }
+        Self {
+            cx,
+            cover,
+            part_9,
+            part_11,
+            description,
+            modifier_description,
+            line_entities,
+        }
+    }
+
+    fn set_data(&self, data: &InfoWindowData) {
+        let mut lines = vec![(data.title_key.to_owned(), vec![])];
+        lines.extend(data.lines.iter().cloned());
+        for item in data.modifiers.iter() {
+            lines.push((
+                "battle-info-modifier".into(),
+                vec![
+                    localization::get_text(&item.name_key),
+                    item.turns.to_string(),
+                ],
+            ));
+        }
+        if lines.len() > MAX_LINES {
+            let rest = lines.len() - (MAX_LINES - 1);
+            lines.truncate(MAX_LINES - 1);
+            lines.push(("battle-info-modifier-more".into(), vec![rest.to_string()]));
+        }
+
+        for (i, &entity) in self.line_entities.iter().enumerate() {
+            if let Some((key, args)) = lines.get(i) {
+                let args = args.iter().map(|a| a.as_str()).collect::<Vec<_>>();
+                self.cx.set_text_key(entity, key).unwrap();
+                self.cx.set_text_format_args(entity, &args).unwrap();
+            } else {
+                self.cx.set_text_key(entity, "").unwrap();
+            }
+        }
+
+        self.cx
+            .set_text_key(self.description, &data.description_key)
+            .unwrap();
+        let modifier_descriptions = data
+            .modifiers
+            .iter()
+            .map(|item| {
+                let args = item
+                    .description_args
+                    .iter()
+                    .map(|a| a.as_str())
+                    .collect::<Vec<_>>();
+                format!(
+                    "{}: {}",
+                    localization::get_text(&item.name_key),
+                    localization::get_formatted_text(&item.description_key, &args)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        self.cx
+            .set_text_format_args(self.modifier_description, &[&modifier_descriptions])
+            .unwrap();
+    }
+
+    async fn show(&self) {
+        let mut anims = vec![
+            self.cx
+                .play_animation(self.cover, "/animation/battle/window-cover-fade-in.yml"),
+            self.cx
+                .play_animation(self.part_9, "/animation/battle/window-item-fade-in.yml"),
+            self.cx
+                .play_animation(self.part_11, "/animation/battle/window-item-fade-in.yml"),
+            self.cx.play_animation(
+                self.description,
+                "/animation/battle/window-item-fade-in.yml",
+            ),
+            self.cx.play_animation(
+                self.modifier_description,
+                "/animation/battle/window-item-fade-in.yml",
+            ),
+        ];
+        for &entity in &self.line_entities {
+            anims.push(
+                self.cx
+                    .play_animation(entity, "/animation/battle/window-item-fade-in.yml"),
+            );
+        }
+        try_join_all(anims).await.expect("animation not found");
+    }
+
+    async fn hide(&self) {
+        let mut anims = vec![
+            self.cx
+                .play_animation(self.cover, "/animation/battle/window-cover-fade-out.yml"),
+            self.cx
+                .play_animation(self.part_9, "/animation/battle/window-item-fade-out.yml"),
+            self.cx
+                .play_animation(self.part_11, "/animation/battle/window-item-fade-out.yml"),
+            self.cx.play_animation(
+                self.description,
+                "/animation/battle/window-item-fade-out.yml",
+            ),
+            self.cx.play_animation(
+                self.modifier_description,
+                "/animation/battle/window-item-fade-out.yml",
+            ),
+        ];
+        for &entity in &self.line_entities {
+            anims.push(
+                self.cx
+                    .play_animation(entity, "/animation/battle/window-item-fade-out.yml"),
+            );
+        }
+        try_join_all(anims).await.expect("animation not found");
+    }
+
+    pub(super) async fn open(&self, data: &InfoWindowData) {
+        self.set_data(data);
+        self.show().await;
+        select! {
+            _ = input::wait_select_button(self.cx).fuse() => (),
+            _ = input::wait_cancel_button(self.cx).fuse() => (),
+            _ = input::wait_left_trigger(self.cx).fuse() => (),
+            _ = input::wait_right_trigger(self.cx).fuse() => (),
+        }
+        self.cx.play_sfx("/audio/sfx/cancel.ogg");
+        self.hide().await;
+    }
+}
+impl<'a> Drop for InfoWindow<'a> {
+    fn drop(&mut self) {
+        self.cx.delete_entity(self.cover);
+        self.cx.delete_entity(self.part_9);
+        self.cx.delete_entity(self.part_11);
+        self.cx.delete_entity(self.description);
+        self.cx.delete_entity(self.modifier_description);
+        for entity in self.line_entities.drain(0..) {
+            self.cx.delete_entity(entity);
+        }
+    }
+}
@@ -11,6 +11,7 @@ pub(super) struct MenuView<'a> {
     items: Entity,
     player_info: Entity,
     enemy_info: Entity,
+    escape: Entity,
 }
 impl<'a> MenuView<'a> {
     pub(super) fn new(cx: &'a AnimationEngineContext) -> Self {
@@ -85,6 +86,16 @@ impl<'a> MenuView<'a> {
             rotation: -0.0872665,
             ..Default::default()
         });
+        let escape = cx.add_text(AddTextInfo {
+            key: "battle-menu-escape".into(),
+            font_size: 36.0,
+            x: 31.438045,
+            y: 510.0,
+            z: 380,
+            a: 0.0,
+            rotation: -0.0872665,
+            ..Default::default()
+        });
         Self {
             cx,
             part_2,
@@ -95,6 +106,7 @@ impl<'a> MenuView<'a> {
             items,
             player_info,
             enemy_info,
+            escape,
         }
     }
 
@@ -112,6 +124,7 @@ impl<'a> MenuView<'a> {
             self.cx.set_opacity(self.items, 1.0).unwrap();
             self.cx.set_opacity(self.player_info, 1.0).unwrap();
             self.cx.set_opacity(self.enemy_info, 1.0).unwrap();
+            self.cx.set_opacity(self.escape, 1.0).unwrap();
         } else {
             self.cx.set_opacity(self.part_4, 0.3).unwrap();
             self.cx.set_opacity(self.attack, 0.3).unwrap();
@@ -119,6 +132,7 @@ impl<'a> MenuView<'a> {
             self.cx.set_opacity(self.items, 0.3).unwrap();
             self.cx.set_opacity(self.player_info, 0.3).unwrap();
             self.cx.set_opacity(self.enemy_info, 0.3).unwrap();
+            self.cx.set_opacity(self.escape, 0.3).unwrap();
         }
     }
 
@@ -142,6 +156,8 @@ impl<'a> MenuView<'a> {
                 self.enemy_info,
                 "/animation/battle/menu-enemy-info-show.yml"
             ),
+            self.cx
+                .play_animation(self.escape, "/animation/battle/menu-escape-show.yml"),
         )
         .expect("animation not found");
         self.cx
@@ -171,6 +187,8 @@ impl<'a> MenuView<'a> {
                 self.enemy_info,
                 "/animation/battle/menu-enemy-info-hide.yml"
             ),
+            self.cx
+                .play_animation(self.escape, "/animation/battle/menu-escape-hide.yml"),
         )
         .expect("animation not found");
     }
@@ -185,5 +203,6 @@ impl<'a> Drop for MenuView<'a> {
         self.cx.delete_entity(self.items);
         self.cx.delete_entity(self.player_info);
         self.cx.delete_entity(self.enemy_info);
+        self.cx.delete_entity(self.escape);
     }
 }
@@ -0,0 +1,51 @@
+use animation_engine::*;
+
+use crate::game::battle::battle_view::ModifierViewItem;
+use crate::localization;
+
+const MAX_ITEMS: usize = 7;
+
+pub(super) struct ModifierListView<'a> {
+    cx: &'a AnimationEngineContext,
+    texts: Vec<Entity>,
+}
+impl<'a> ModifierListView<'a> {
+    pub(super) fn new(cx: &'a AnimationEngineContext, x: f32, y: f32, step_y: f32) -> Self {
+        let texts = (0..MAX_ITEMS)
+            .map(|i| {
+                cx.add_text(AddTextInfo {
+                    key: "battle-modifier-text".into(),
+                    font_size: 24.0,
+                    x,
+                    y: y + step_y * i as f32,
+                    z: 490,
+                    a: 0.0,
+                    rotation: -0.0872665,
+                    ..Default::default()
+                })
+            })
+            .collect();
+        Self { cx, texts }
+    }
+
+    pub(super) fn set_items(&self, items: &[ModifierViewItem]) {
+        for (i, &text) in self.texts.iter().enumerate() {
+            if let Some(item) = items.get(i) {
+                let name = localization::get_text(&item.name_key);
+                self.cx
+                    .set_text_format_args(text, &[&name, &item.turns.to_string()])
+                    .unwrap();
+                self.cx.set_opacity(text, 1.0).unwrap();
+            } else {
+                self.cx.set_opacity(text, 0.0).unwrap();
+            }
+        }
+    }
+}
+impl<'a> Drop for ModifierListView<'a> {
+    fn drop(&mut self) {
+        for &text in self.texts.iter() {
+            self.cx.delete_entity(text);
+        }
+    }
+}
@@ -1,5 +1,6 @@
 use animation_engine::executor::*;
 use animation_engine::*;
+use futures::future::join_all;
 use futures::{join, try_join};
 
 use crate::game::battle::battle_view::damage_number_view::*;
@@ -156,12 +157,21 @@ impl<'a> PlayerView<'a> {
             .unwrap();
     }
 
-    pub(super) fn damage_animation(&self, damage: i32) {
+    pub(super) fn damage_animation(&self, damage: i32, overlays: &[DamageOverlay]) {
         let cx = self.cx.clone();
         let player_image = self.player_image;
         let player_shadow_image = self.player_shadow_image;
+        let overlays = overlays.to_vec();
         spawn(async move {
-            let damage = DamageNumberView::new_damage(&cx, damage, 1160.0, 570.0, 495);
+            let damage = if overlays.contains(&DamageOverlay::Critical) {
+                DamageNumberView::new_critical(&cx, damage, 1160.0, 570.0, 495)
+            } else {
+                DamageNumberView::new_damage(&cx, damage, 1160.0, 570.0, 495)
+            };
+            let overlays = overlays
+                .iter()
+                .map(|&overlay| DamageOverlayView::new(&cx, overlay, 1160.0, 530.0, 497))
+                .collect::<Vec<_>>();
             let result = join!(
                 cx.play_animation(player_image, "/animation/battle/player-image-damage.yml"),
                 cx.play_animation(
@@ -169,12 +179,31 @@ impl<'a> PlayerView<'a> {
                     "/animation/battle/player-shadow-image-damage.yml"
                 ),
                 damage.start_animation("/animation/battle/player-damage-number-animation.yml"),
+                join_all(
+                    overlays
+                        .iter()
+                        .enumerate()
+                        .map(|(slot, overlay)| async move {
+                            let animation =
+                                format!("/animation/battle/player-overlay-animation-{}.yml", slot);
+                            overlay.start_animation(&animation).await
+                        })
+                ),
             );
             result.0.expect("animation not found");
             result.1.expect("animation not found");
         });
     }
 
+    pub(super) fn miss_animation(&self) {
+        let cx = self.cx.clone();
+        spawn(async move {
+            DamageOverlayView::new(&cx, DamageOverlay::Miss, 1160.0, 530.0, 497)
+                .start_animation("/animation/battle/player-overlay-animation-0.yml")
+                .await;
+        });
+    }
+
     pub(super) fn heal_animation(&self, heal: i32) {
         let cx = self.cx.clone();
         spawn(async move {
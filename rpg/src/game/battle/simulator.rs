@@ -0,0 +1,281 @@
+use rand::prelude::*;
+use serde::Deserialize;
+
+use crate::game::battle::battle_model::*;
+use crate::game::PlayerState;
+use crate::game_data::*;
+
+/// 決着がつかない組み合わせで無限に回らないようにするためのターン上限
+const MAX_TURNS: u32 = 200;
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum SimulatedResult {
+    Win,
+    Lose,
+    Escaped,
+    TurnLimit,
+}
+
+pub struct SimulatedBattle {
+    pub result: SimulatedResult,
+    pub turns: u32,
+    pub player_hp: i32,
+    pub player_max_hp: i32,
+}
+
+pub struct EnemySnapshot {
+    pub hp: i32,
+    pub max_hp: i32,
+    pub alive: bool,
+}
+
+/// ポリシーがコマンドを選ぶときに参照できる戦闘の状態
+pub struct BattleSnapshot<'a> {
+    pub player_hp: (i32, i32),
+    pub player_tp: (i32, i32),
+    pub enemies: Vec<EnemySnapshot>,
+    /// 使用可能なスキル。先頭は常に通常攻撃
+    pub skills: Vec<&'a SkillData>,
+    /// 使用可能なアイテム。同じアイテムは1つにまとめる
+    pub items: Vec<&'a ItemData>,
+}
+impl<'a> BattleSnapshot<'a> {
+    pub fn alive_enemies(&self) -> Vec<usize> {
+        (0..self.enemies.len())
+            .filter(|&i| self.enemies[i].alive)
+            .collect()
+    }
+
+    pub fn weakest_enemy(&self) -> usize {
+        self.alive_enemies()
+            .into_iter()
+            .min_by_key(|&i| self.enemies[i].hp)
+            .unwrap()
+    }
+}
+
+/// 第二要素は対象とする敵のインデックス
+pub enum SimulatedCommand {
+    Skill(SkillId, usize),
+    Item(ItemId, usize),
+    Escape,
+}
+impl From<SimulatedCommand> for BattleCommand {
+    fn from(command: SimulatedCommand) -> Self {
+        match command {
+            SimulatedCommand::Skill(skill_id, target) => BattleCommand::Skill(skill_id, target),
+            SimulatedCommand::Item(item_id, target) => BattleCommand::Item(item_id, target),
+            SimulatedCommand::Escape => BattleCommand::Escape,
+        }
+    }
+}
+
+pub trait BattlePolicy {
+    fn select_command(&mut self, snapshot: &BattleSnapshot, rng: &mut StdRng) -> SimulatedCommand;
+
+    /// 戦闘の開始時に呼ばれる。戦闘をまたいで状態を持ち越さないように戻す
+    fn reset(&mut self) {}
+}
+
+/// 使用可能なスキルとアイテムから無作為に選ぶ
+pub struct RandomPolicy;
+impl BattlePolicy for RandomPolicy {
+    fn select_command(&mut self, snapshot: &BattleSnapshot, rng: &mut StdRng) -> SimulatedCommand {
+        let target = *snapshot.alive_enemies().choose(rng).unwrap();
+        let index = rng.gen_range(0..snapshot.skills.len() + snapshot.items.len());
+        if index < snapshot.skills.len() {
+            SimulatedCommand::Skill(snapshot.skills[index].id, target)
+        } else {
+            SimulatedCommand::Item(snapshot.items[index - snapshot.skills.len()].id, target)
+        }
+    }
+}
+
+/// HPが減っていれば回復アイテムを使い、そうでなければ最も威力の高いスキルで一番弱っている敵を狙う
+pub struct GreedyPolicy {
+    pub heal_below: f64,
+}
+impl Default for GreedyPolicy {
+    fn default() -> Self {
+        Self { heal_below: 0.3 }
+    }
+}
+impl GreedyPolicy {
+    fn damage_multiplier(effects: &[Effect]) -> f64 {
+        effects
+            .iter()
+            .map(|effect| match *effect {
+                Effect::Damage { multiplier } => multiplier,
+                Effect::MultiHit { multiplier, hits } => multiplier * hits as f64,
+                _ => 0.0,
+            })
+            .sum()
+    }
+
+    fn is_heal(effects: &[Effect]) -> bool {
+        effects
+            .iter()
+            .any(|effect| matches!(effect, Effect::Heal { .. } | Effect::HealRatio { .. }))
+    }
+}
+impl BattlePolicy for GreedyPolicy {
+    fn select_command(&mut self, snapshot: &BattleSnapshot, _: &mut StdRng) -> SimulatedCommand {
+        let target = snapshot.weakest_enemy();
+        let (hp, max_hp) = snapshot.player_hp;
+        if (hp as f64) < max_hp as f64 * self.heal_below {
+            if let Some(item) = snapshot.items.iter().find(|i| Self::is_heal(&i.effects)) {
+                return SimulatedCommand::Item(item.id, target);
+            }
+        }
+        let skill = snapshot
+            .skills
+            .iter()
+            .max_by(|a, b| {
+                Self::damage_multiplier(&a.effects)
+                    .partial_cmp(&Self::damage_multiplier(&b.effects))
+                    .unwrap()
+            })
+            .unwrap();
+        SimulatedCommand::Skill(skill.id, target)
+    }
+}
+
+#[derive(Deserialize, Clone, Copy)]
+pub enum ScriptedCommand {
+    Skill(SkillId),
+    Item(ItemId),
+    Escape,
+}
+
+/// 決められたコマンドを順番に繰り返す。使用できないコマンドの場合は通常攻撃で代用する
+pub struct ScriptedPolicy {
+    commands: Vec<ScriptedCommand>,
+    index: usize,
+}
+impl ScriptedPolicy {
+    pub fn new(commands: Vec<ScriptedCommand>) -> anyhow::Result<Self> {
+        if commands.is_empty() {
+            anyhow::bail!("Scripted policy needs at least one command");
+        }
+        Ok(Self { commands, index: 0 })
+    }
+}
+impl BattlePolicy for ScriptedPolicy {
+    fn reset(&mut self) {
+        self.index = 0;
+    }
+
+    fn select_command(&mut self, snapshot: &BattleSnapshot, _: &mut StdRng) -> SimulatedCommand {
+        let command = self.commands[self.index % self.commands.len()];
+        self.index += 1;
+
+        let target = snapshot.alive_enemies()[0];
+        match command {
+            ScriptedCommand::Skill(skill_id)
+                if snapshot.skills.iter().any(|s| s.id == skill_id) =>
+            {
+                SimulatedCommand::Skill(skill_id, target)
+            }
+            ScriptedCommand::Item(item_id) if snapshot.items.iter().any(|i| i.id == item_id) => {
+                SimulatedCommand::Item(item_id, target)
+            }
+            ScriptedCommand::Escape => SimulatedCommand::Escape,
+            _ => SimulatedCommand::Skill(SkillId(0), target),
+        }
+    }
+}
+
+fn snapshot<'a>(
+    model: &BattleModel,
+    player_data: &'a PlayerData,
+    data: SelectCommandData<'a>,
+) -> BattleSnapshot<'a> {
+    let attack = player_data
+        .skills
+        .iter()
+        .find(|s| s.id == SkillId(0))
+        .unwrap();
+    let skills = std::iter::once(attack)
+        .chain(
+            data.skills
+                .iter()
+                .zip(data.skill_data)
+                .filter(|(item, _)| item.active)
+                .map(|(_, skill)| skill),
+        )
+        .collect();
+    let mut items = data
+        .items
+        .iter()
+        .zip(data.item_data)
+        .filter(|(item, _)| item.active)
+        .map(|(_, item)| item)
+        .collect::<Vec<_>>();
+    items.dedup_by_key(|i| i.id);
+    let enemies = data
+        .enemy_alive
+        .iter()
+        .enumerate()
+        .map(|(index, &alive)| {
+            let (hp, max_hp) = model.enemy_hp(index);
+            EnemySnapshot { hp, max_hp, alive }
+        })
+        .collect();
+    BattleSnapshot {
+        player_hp: model.player_hp(),
+        player_tp: model.player_tp(),
+        enemies,
+        skills,
+        items,
+    }
+}
+
+/// 画面を使わずに1回分の戦闘を最後まで進める。戦闘後のHPとTPは`player_state`に書き戻される
+pub fn simulate_battle(
+    player_data: &PlayerData,
+    item_data: &Vec<ItemData>,
+    enemy_data: &[&EnemyData],
+    player_state: &mut PlayerState,
+    policy: &mut dyn BattlePolicy,
+    rng: &mut StdRng,
+) -> SimulatedBattle {
+    let mut model = BattleModel::new(player_data, item_data, enemy_data, player_state, |key| {
+        key.to_owned()
+    });
+    policy.reset();
+
+    let result = loop {
+        if model.turn_count() >= MAX_TURNS {
+            break SimulatedResult::TurnLimit;
+        }
+
+        let (_, turn_result) = model.turn_start(player_state, rng);
+        match turn_result {
+            BattleTurnResult::Win => break SimulatedResult::Win,
+            BattleTurnResult::Lose => break SimulatedResult::Lose,
+            BattleTurnResult::Escaped => break SimulatedResult::Escaped,
+            BattleTurnResult::Continue => (),
+        }
+
+        let data = model.select_command_data(player_state);
+        let snapshot = snapshot(&model, player_data, data);
+        let command = policy.select_command(&snapshot, rng);
+
+        let (_, turn_result) = model.process_turn(player_state, command.into(), rng);
+        match turn_result {
+            BattleTurnResult::Win => break SimulatedResult::Win,
+            BattleTurnResult::Lose => break SimulatedResult::Lose,
+            BattleTurnResult::Escaped => break SimulatedResult::Escaped,
+            BattleTurnResult::Continue => (),
+        }
+    };
+
+    model.write_back_player_state(player_state);
+    let (player_hp, player_max_hp) = model.player_hp();
+    SimulatedBattle {
+        result,
+        turns: model.turn_count(),
+        player_hp,
+        player_max_hp,
+    }
+}
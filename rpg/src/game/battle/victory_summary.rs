@@ -0,0 +1,118 @@
+use animation_engine::*;
+use futures::future::try_join_all;
+use futures::{select, try_join, FutureExt};
+use log::trace;
+
+use crate::game::battle::BattleRewards;
+use crate::game_data::*;
+use crate::input;
+
+/// 戦闘終了後、暗転した画面の上に獲得した報酬を表示する
+struct VictorySummary<'a> {
+    cx: &'a AnimationEngineContext,
+    bg: Entity,
+    texts: Vec<Entity>,
+}
+impl<'a> VictorySummary<'a> {
+    fn new(
+        cx: &'a AnimationEngineContext,
+        rewards: &BattleRewards,
+        item_data: &[ItemData],
+    ) -> Self {
+        let bg = cx.add_image(AddImageInfo {
+            name: "/image/ui/menu-bg.png".into(),
+            x: -25.0,
+            y: 0.0,
+            z: 510,
+            a: 0.0,
+            ..Default::default()
+        });
+
+        let mut lines = vec![(
+            "battle-victory-gold".to_string(),
+            vec![rewards.gold.to_string()],
+        )];
+        for &(item_id, count) in rewards.items.iter() {
+            let item = item_data.iter().find(|i| i.id == item_id).unwrap();
+            lines.push((item.item_name_with_count.clone(), vec![count.to_string()]));
+        }
+        if rewards.items.is_empty() {
+            lines.push(("battle-victory-no-item".into(), vec![]));
+        }
+        if !rewards.skill_candidates.is_empty() {
+            lines.push(("battle-victory-skill".into(), vec![]));
+        }
+
+        let mut texts = vec![cx.add_text(AddTextInfo {
+            key: "battle-victory-title".into(),
+            font_size: 72.0,
+            x: 500.0,
+            y: 30.0,
+            z: 515,
+            a: 0.0,
+            ..Default::default()
+        })];
+        for (i, (key, format_args)) in lines.into_iter().enumerate() {
+            texts.push(cx.add_text(AddTextInfo {
+                key,
+                format_args,
+                font_size: 30.0,
+                x: 420.0,
+                y: 180.0 + 52.0 * i as f32,
+                z: 515,
+                a: 0.0,
+                ..Default::default()
+            }));
+        }
+
+        Self { cx, bg, texts }
+    }
+
+    async fn start(&self) {
+        trace!("start victory summary enter animation");
+
+        try_join!(
+            self.cx
+                .play_animation(self.bg, "/animation/victory/bg-enter.yml"),
+            try_join_all(self.texts.iter().map(|&text| {
+                self.cx
+                    .play_animation(text, "/animation/victory/content-enter.yml")
+            })),
+        )
+        .expect("animation not found");
+
+        select! {
+            _ = input::wait_select_button(self.cx).fuse() => (),
+            _ = input::wait_cancel_button(self.cx).fuse() => (),
+        }
+        self.cx.play_sfx("/audio/sfx/select.ogg");
+
+        trace!("start victory summary close animation");
+
+        try_join!(
+            self.cx
+                .play_animation(self.bg, "/animation/victory/bg-close.yml"),
+            try_join_all(self.texts.iter().map(|&text| {
+                self.cx
+                    .play_animation(text, "/animation/victory/content-close.yml")
+            })),
+        )
+        .expect("animation not found");
+    }
+}
+impl<'a> Drop for VictorySummary<'a> {
+    fn drop(&mut self) {
+        self.cx.delete_entity(self.bg);
+        for &text in self.texts.iter() {
+            self.cx.delete_entity(text);
+        }
+    }
+}
+
+pub(super) async fn victory_summary(
+    cx: &AnimationEngineContext,
+    rewards: &BattleRewards,
+    item_data: &[ItemData],
+) {
+    VictorySummary::new(cx, rewards, item_data).start().await;
+}
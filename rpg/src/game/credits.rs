@@ -0,0 +1,125 @@
+use animation_engine::executor::*;
+use animation_engine::*;
+use futures::future::try_join_all;
+use futures::{select, FutureExt};
+use log::{info, trace};
+
+use crate::game::game;
+use crate::game_data::*;
+use crate::input;
+
+const LINE_X: f32 = 460.0;
+const HEADING_X: f32 = 420.0;
+const LINE_HEIGHT: f32 = 60.0;
+/// 最後の行がこの高さまで上がったらスクロールを止める
+const STOP_Y: f32 = 330.0;
+
+struct CreditsScene<'a> {
+    cx: &'a AnimationEngineContext,
+    cover: Entity,
+    /// エンティティと、スクロール開始時のy座標
+    lines: Vec<(Entity, f32, f32)>,
+}
+impl<'a> CreditsScene<'a> {
+    fn new(cx: &'a AnimationEngineContext, credits_data: &CreditsData) -> Self {
+        trace!("new credits scene.");
+
+        let cover = cx.add_rect(AddRectInfo {
+            width: 1280.0,
+            height: 720.0,
+            r: 0.0,
+            g: 0.0,
+            b: 0.0,
+            a: 1.0,
+            z: 400,
+            ..Default::default()
+        });
+
+        let mut lines = vec![];
+        let mut y = 720.0;
+        for line in credits_data.lines.iter() {
+            let (key, x, font_size, color) = match line {
+                CreditsLine::Title { text } => (text, HEADING_X, 56.0, (1.0, 1.0, 1.0)),
+                CreditsLine::Heading { text } => (text, HEADING_X, 26.0, (212.0 / 255.0, 1.0, 1.0)),
+                CreditsLine::Name { text } => (text, LINE_X, 36.0, (1.0, 1.0, 1.0)),
+                CreditsLine::Space => {
+                    y += LINE_HEIGHT;
+                    continue;
+                }
+            };
+            let (r, g, b) = color;
+            let entity = cx.add_text(AddTextInfo {
+                key: key.clone(),
+                font_size,
+                x,
+                y,
+                z: 410,
+                r,
+                g,
+                b,
+                ..Default::default()
+            });
+            lines.push((entity, x, y));
+            y += LINE_HEIGHT;
+        }
+
+        Self { cx, cover, lines }
+    }
+
+    /// 行が画面外に出てもエンティティは消さず、位置だけを更新する
+    async fn scroll(&self, scroll_speed: f32) {
+        let last_y = self.lines.last().map_or(STOP_Y, |&(_, _, y)| y);
+        let mut offset = 0.0;
+        while last_y - offset > STOP_Y {
+            offset += scroll_speed;
+            for &(entity, x, y) in self.lines.iter() {
+                self.cx.set_position(entity, x, y - offset, 410).unwrap();
+            }
+            next_frame().await;
+        }
+    }
+
+    async fn start(&self, credits_data: &CreditsData) {
+        if let Some(bgm) = &credits_data.bgm {
+            self.cx.play_bgm(bgm);
+        }
+
+        trace!("start credits scroll");
+
+        // 決定ボタンでスクロールを飛ばせる
+        select! {
+            _ = self.scroll(credits_data.scroll_speed).fuse() => (),
+            _ = input::wait_select_button(self.cx).fuse() => (),
+        }
+
+        trace!("finish credits scroll");
+
+        input::wait_select_button(self.cx).await;
+        self.cx.play_sfx("/audio/sfx/select.ogg");
+        try_join_all(self.lines.iter().map(|&(entity, _, _)| {
+            self.cx
+                .play_animation(entity, "/animation/ending/credits-close.yml")
+        }))
+        .await
+        .expect("animation not found");
+        self.cx.stop_bgm();
+    }
+}
+impl<'a> Drop for CreditsScene<'a> {
+    fn drop(&mut self) {
+        trace!("drop credits scene.");
+
+        self.cx.delete_entity(self.cover);
+        for &(entity, _, _) in self.lines.iter() {
+            self.cx.delete_entity(entity);
+        }
+    }
+}
+
+pub async fn credits(cx: &AnimationEngineContext, global_data: &game::GlobalData) {
+    info!("Enter Credits Scene!");
+    let credits_data = global_data.game_data.credits_data();
+    CreditsScene::new(cx, credits_data)
+        .start(credits_data)
+        .await
+}
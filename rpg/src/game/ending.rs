@@ -0,0 +1,212 @@
+use animation_engine::*;
+use futures::try_join;
+use log::{info, trace};
+
+use crate::game::game;
+use crate::game_data::*;
+use crate::input;
+
+struct EndingScene<'a> {
+    cx: &'a AnimationEngineContext,
+    bg: Entity,
+    cover: Entity,
+    player_image: Entity,
+    player_shadow_image: Entity,
+    cursor_bg: Entity,
+    cursor_top: Entity,
+    text_message: Entity,
+    text_next: Entity,
+}
+impl<'a> EndingScene<'a> {
+    fn new(cx: &'a AnimationEngineContext, player_data: &PlayerData) -> Self {
+        trace!("new ending scene.");
+
+        let bg = cx.add_image(AddImageInfo {
+            name: "/image/ui/opening-bg.png".into(),
+            x: 400.0,
+            z: 200,
+            ..Default::default()
+        });
+        let cover = cx.add_rect(AddRectInfo {
+            width: 1280.0,
+            height: 720.0,
+            r: 0.0,
+            g: 0.0,
+            b: 0.0,
+            a: 1.0,
+            z: 400,
+            ..Default::default()
+        });
+        let player_image = cx.add_image(AddImageInfo {
+            name: player_data.image.clone(),
+            x: 700.0,
+            z: 340,
+            a: 0.0,
+            ..Default::default()
+        });
+        let player_shadow_image = cx.add_image(AddImageInfo {
+            name: player_data.shadow_image.clone(),
+            x: 650.0,
+            z: 335,
+            a: 0.0,
+            ..Default::default()
+        });
+        let cursor_bg = cx.add_image(AddImageInfo {
+            name: "/image/ui/cursor-bg.png".into(),
+            x: 100.0,
+            y: 620.0,
+            z: 270,
+            a: 0.0,
+            ..Default::default()
+        });
+        let cursor_top = cx.add_image(AddImageInfo {
+            name: "/image/ui/cursor-top.png".into(),
+            x: 100.0,
+            y: 620.0,
+            z: 280,
+            a: 0.0,
+            ..Default::default()
+        });
+        let text_message = cx.add_text(AddTextInfo {
+            font_size: 26.0,
+            x: 120.0,
+            y: 60.0,
+            z: 275,
+            r: 212.0 / 255.0,
+            g: 1.0,
+            b: 1.0,
+            a: 0.0,
+            ..Default::default()
+        });
+        let text_next = cx.add_text(AddTextInfo {
+            key: "ending-next".into(),
+            font_size: 36.0,
+            x: 150.0,
+            y: 623.0,
+            z: 275,
+            a: 0.0,
+            ..Default::default()
+        });
+        Self {
+            cx,
+            bg,
+            cover,
+            player_image,
+            player_shadow_image,
+            cursor_bg,
+            cursor_top,
+            text_message,
+            text_next,
+        }
+    }
+
+    async fn enter_animation(&self) {
+        trace!("start ending enter animation");
+
+        try_join!(
+            self.cx
+                .play_animation(self.cover, "/animation/ending/cover-enter.yml"),
+            self.cx.play_animation(
+                self.player_image,
+                "/animation/ending/player-image-fade-in.yml"
+            ),
+            self.cx.play_animation(
+                self.player_shadow_image,
+                "/animation/ending/player-shadow-image-fade-in.yml"
+            ),
+        )
+        .expect("animation not found");
+
+        trace!("finish ending enter animation");
+    }
+
+    async fn message(&self, text: &str) {
+        self.cx
+            .set_text_key(self.text_message, text)
+            .unwrap_or_else(|_| panic!("Failed to get text: {}", text));
+
+        self.cx
+            .play_animation(self.text_message, "/animation/ending/text-fade-in.yml")
+            .await
+            .expect("animation not found");
+
+        self.cx.play_sfx("/audio/sfx/cursor.ogg");
+        try_join!(
+            self.cx
+                .play_animation(self.text_next, "/animation/ending/next-fade-in.yml"),
+            self.cx
+                .play_animation(self.cursor_bg, "/animation/ending/next-fade-in.yml"),
+            self.cx
+                .play_animation(self.cursor_top, "/animation/ending/next-fade-in.yml")
+        )
+        .expect("animation not found");
+
+        input::wait_select_button(self.cx).await;
+
+        self.cx.play_sfx("/audio/sfx/select.ogg");
+        try_join!(
+            self.cx
+                .play_animation(self.text_message, "/animation/ending/text-fade-out.yml"),
+            self.cx
+                .play_animation(self.text_next, "/animation/ending/next-fade-out.yml"),
+            self.cx
+                .play_animation(self.cursor_bg, "/animation/ending/next-fade-out.yml"),
+            self.cx
+                .play_animation(self.cursor_top, "/animation/ending/next-fade-out.yml")
+        )
+        .expect("animation not found");
+    }
+
+    async fn close_animation(&self) {
+        trace!("start ending close animation");
+
+        self.cx
+            .play_animation(self.cover, "/animation/ending/cover-close.yml")
+            .await
+            .expect("animation not found");
+
+        trace!("finish ending close animation");
+    }
+
+    async fn start(&self, ending_data: &EndingData, player_index: usize) {
+        self.cx.play_bgm(&ending_data.bgm);
+        self.enter_animation().await;
+
+        let ending = ending_data
+            .endings
+            .iter()
+            .find(|e| e.player_index == player_index)
+            .unwrap_or_else(|| panic!("No ending data. player index: {}", player_index));
+        for text in ending.messages.iter() {
+            self.message(text).await;
+        }
+
+        self.close_animation().await;
+    }
+}
+impl<'a> Drop for EndingScene<'a> {
+    fn drop(&mut self) {
+        trace!("drop ending scene.");
+
+        self.cx.delete_entity(self.bg);
+        self.cx.delete_entity(self.cover);
+        self.cx.delete_entity(self.player_image);
+        self.cx.delete_entity(self.player_shadow_image);
+        self.cx.delete_entity(self.cursor_bg);
+        self.cx.delete_entity(self.cursor_top);
+        self.cx.delete_entity(self.text_message);
+        self.cx.delete_entity(self.text_next);
+    }
+}
+
+pub async fn ending(
+    cx: &AnimationEngineContext,
+    global_data: &game::GlobalData,
+    player_index: usize,
+) {
+    info!("Enter Ending Scene!");
+    let game_data = &global_data.game_data;
+    EndingScene::new(cx, &game_data.player_data()[player_index])
+        .start(game_data.ending_data(), player_index)
+        .await
+}
@@ -10,6 +10,7 @@ mod cover;
 mod current_depth;
 mod explore_scene;
 mod message_list;
+mod player_status;
 mod skill_item_list_window;
 mod window_frame;
 
@@ -20,16 +21,21 @@ use cover::*;
 use current_depth::*;
 use explore_scene::*;
 use message_list::*;
+use player_status::*;
 use skill_item_list_window::*;
 use window_frame::*;
 
-pub use explore_scene::ExploreResult;
+pub use explore_scene::{Checkpoint, ExploreResult, GameOverSummary};
 
 pub async fn explore(
     cx: &AnimationEngineContext,
     global_data: &mut game::GlobalData,
     player_index: usize,
+    run_seed: Option<u64>,
+    checkpoint: Option<Checkpoint>,
 ) -> ExploreResult {
     info!("Enter Explore Scene!");
-    ExploreScene::new(cx, player_index).start(global_data).await
+    ExploreScene::new(cx, player_index, run_seed)
+        .start(global_data, checkpoint)
+        .await
 }
@@ -1,5 +1,7 @@
 use animation_engine::*;
 
+use crate::game_data::BattleTime;
+
 pub(super) struct Background<'a> {
     cx: &'a AnimationEngineContext,
     bg: Entity,
@@ -42,6 +44,17 @@ impl<'a> Background<'a> {
         }
     }
 
+    /// チェックポイントから再開するときに、アニメーションなしで時間帯を合わせる
+    pub(super) fn set_time(&self, time: BattleTime) {
+        let (morning, night) = match time {
+            BattleTime::Morning => (0.25, 0.0),
+            BattleTime::Afternoon => (0.0, 0.0),
+            BattleTime::Night => (0.0, 0.25),
+        };
+        self.cx.set_opacity(self.morning_cover, morning).unwrap();
+        self.cx.set_opacity(self.night_cover, night).unwrap();
+    }
+
     pub(super) async fn change_to_afternoon(&self) {
         self.cx
             .play_animation(
@@ -44,8 +44,12 @@ impl<'a> CurrentDepth<'a> {
             .unwrap();
     }
 
-    pub(super) fn increment(&mut self) {
-        self.depth += 1;
+    pub(super) fn depth(&self) -> u32 {
+        self.depth
+    }
+
+    pub(super) fn set_depth(&mut self, depth: u32) {
+        self.depth = depth;
         self.cx
             .set_text_format_args(
                 self.count_text,
@@ -53,6 +57,10 @@ impl<'a> CurrentDepth<'a> {
             )
             .unwrap();
     }
+
+    pub(super) fn increment(&mut self) {
+        self.set_depth(self.depth + 1);
+    }
 }
 impl<'a> Drop for CurrentDepth<'a> {
     fn drop(&mut self) {
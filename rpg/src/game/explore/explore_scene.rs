@@ -2,7 +2,7 @@ use animation_engine::executor::*;
 use animation_engine::*;
 use async_recursion::async_recursion;
 use futures::{select, FutureExt};
-use log::{info, trace};
+use log::{info, trace, warn};
 use rand::distributions::*;
 use rand::prelude::*;
 
@@ -11,47 +11,201 @@ use crate::game::explore::*;
 use crate::game::*;
 use crate::game_data::*;
 use crate::input;
+use crate::localization;
 use crate::save_data;
 
 enum ProcessEventResult {
     Playing,
-    GameOver,
+    /// 現在のレベルを打ち切って指定したindexのレベルに進む
+    JumpTo(usize),
+    /// 現在のレベルを打ち切ってcount個先のレベルに進む
+    Skip(usize),
+    /// killed_byは倒された敵の名前のキー。イベントで力尽きた場合はNone
+    GameOver {
+        killed_by: Option<String>,
+    },
+}
+
+/// チェックポイントの深さに入った時点の状態
+pub struct Checkpoint {
+    level_index: usize,
+    depth: u32,
+    player_state: PlayerState,
+    explore_bgm: String,
+    time: BattleTime,
+    /// 中断データから再開した場合に、力尽きたときに戻るチェックポイント
+    last_checkpoint: Option<Box<Checkpoint>>,
+}
+impl Checkpoint {
+    fn to_run_state(&self) -> save_data::RunState {
+        let (hp, _) = self.player_state.get_hp();
+        let (tp, _) = self.player_state.get_tp();
+        let (battle_count, turn_count) = self.player_state.get_battle_stats();
+        let (persistent_flags, persistent_counters) = self.player_state.get_persistent_keys();
+        save_data::RunState {
+            level_index: self.level_index,
+            depth: self.depth,
+            hp,
+            tp,
+            gold: self.player_state.get_gold(),
+            items: self.player_state.get_items(),
+            skills: self.player_state.get_skills(),
+            flags: self.player_state.get_flags(),
+            counters: self.player_state.get_counters(),
+            persistent_flags: Some(persistent_flags),
+            persistent_counters: Some(persistent_counters),
+            explore_bgm: self.explore_bgm.clone(),
+            time: self.time,
+            battle_count: Some(battle_count),
+            turn_count: Some(turn_count),
+        }
+    }
+
+    fn from_run_state(state: &save_data::RunState, player_data: &PlayerData) -> Self {
+        let mut player_state = PlayerState::new(player_data);
+        player_state.set_hp(state.hp);
+        player_state.set_tp(state.tp);
+        player_state.add_gold(state.gold);
+        for &(item_id, count) in state.items.iter() {
+            for _ in 0..count {
+                player_state.add_item(item_id);
+            }
+        }
+        for &skill_id in state.skills.iter() {
+            player_state.add_skill(skill_id, &player_data.skills);
+        }
+        for flag in state.flags.iter() {
+            player_state.set_flag(flag);
+        }
+        for (counter, value) in state.counters.iter() {
+            player_state.add_counter(counter, *value);
+        }
+        for flag in state.persistent_flags.iter().flatten() {
+            player_state.mark_persistent_flag(flag);
+        }
+        for counter in state.persistent_counters.iter().flatten() {
+            player_state.mark_persistent_counter(counter);
+        }
+        player_state.restore_battle_stats(
+            state.battle_count.unwrap_or(0),
+            state.turn_count.unwrap_or(0),
+        );
+        Self {
+            level_index: state.level_index,
+            depth: state.depth,
+            player_state,
+            explore_bgm: state.explore_bgm.clone(),
+            time: state.time,
+            last_checkpoint: None,
+        }
+    }
+
+    fn is_valid_run_state(
+        state: &save_data::RunState,
+        player_data: &PlayerData,
+        game_data: &GameData,
+    ) -> bool {
+        state.level_index < game_data.level_data().len()
+            && state
+                .items
+                .iter()
+                .all(|(item_id, _)| game_data.item_data().iter().any(|i| i.id == *item_id))
+            && state
+                .skills
+                .iter()
+                .all(|skill_id| player_data.skills.iter().any(|s| s.id == *skill_id))
+    }
+
+    /// ゲームデータが更新されて中断データと合わなくなった場合はNoneを返す
+    pub fn from_suspend_data(
+        suspend: &save_data::SuspendData,
+        game_data: &GameData,
+    ) -> Option<Self> {
+        let player_data = game_data.player_data().get(suspend.player_index)?;
+        let is_valid = Self::is_valid_run_state(&suspend.state, player_data, game_data)
+            && suspend
+                .checkpoint
+                .iter()
+                .all(|c| Self::is_valid_run_state(c, player_data, game_data));
+        if !is_valid {
+            return None;
+        }
+        let mut checkpoint = Self::from_run_state(&suspend.state, player_data);
+        checkpoint.last_checkpoint = suspend
+            .checkpoint
+            .as_ref()
+            .map(|c| Box::new(Self::from_run_state(c, player_data)));
+        Some(checkpoint)
+    }
+}
+
+pub struct GameOverSummary {
+    pub depth: u32,
+    pub max_depth: u32,
+    pub killed_by: Option<String>,
+    pub checkpoint: Option<Checkpoint>,
 }
 
 pub enum ExploreResult {
     GameClear,
-    GameOver,
+    GameOver(Box<GameOverSummary>),
+}
+
+fn message_args(args: &[MessageArg], player_state: &PlayerState) -> Vec<String> {
+    args.iter()
+        .map(|arg| match arg {
+            MessageArg::Counter { counter } => player_state.counter(counter).to_string(),
+            MessageArg::Flag { flag, set, unset } => {
+                localization::get_text(if player_state.is_flag_set(flag) {
+                    set
+                } else {
+                    unset
+                })
+            }
+        })
+        .collect()
 }
 
 pub(super) struct ExploreScene<'a> {
     cx: &'a AnimationEngineContext,
     player_index: usize,
+    run_seed: Option<u64>,
     _frame: WindowFrame<'a>,
     cover: Cover<'a>,
     current_depth: CurrentDepth<'a>,
+    player_status: PlayerStatus<'a>,
     background: Background<'a>,
     message_list: MessageList<'a>,
     skill_item_list_window: SkillItemListWindow<'a>,
     current_explore_bgm: String,
+    current_time: BattleTime,
 }
 impl<'a> ExploreScene<'a> {
-    pub(super) fn new(cx: &'a AnimationEngineContext, player_index: usize) -> Self {
+    pub(super) fn new(
+        cx: &'a AnimationEngineContext,
+        player_index: usize,
+        run_seed: Option<u64>,
+    ) -> Self {
         let frame = WindowFrame::new(cx);
         let cover = Cover::new(cx);
         let current_depth = CurrentDepth::new(cx);
+        let player_status = PlayerStatus::new(cx);
         let background = Background::new(cx);
         let message_list = MessageList::new(cx);
         let skill_item_list_window = SkillItemListWindow::new(cx);
         Self {
             cx,
             player_index,
+            run_seed,
             _frame: frame,
             cover,
             current_depth,
+            player_status,
             background,
             message_list,
             skill_item_list_window,
             current_explore_bgm: "field-0".into(),
+            current_time: BattleTime::Morning,
         }
     }
 
@@ -104,7 +258,7 @@ impl<'a> ExploreScene<'a> {
     async fn wait_choice(
         &mut self,
         message: impl ToString,
-        choices: &[(u8, &str)],
+        choices: &[(u8, &str, bool)],
         player_state: &PlayerState,
         player_data: &PlayerData,
         item_data: &Vec<ItemData>,
@@ -138,10 +292,50 @@ impl<'a> ExploreScene<'a> {
             .await;
     }
 
+    fn check_condition(
+        &self,
+        condition: &LevelCondition,
+        player_state: &PlayerState,
+        player_data: &PlayerData,
+    ) -> bool {
+        match condition {
+            LevelCondition::HasItem { item_id, count } => {
+                player_state.item_count(*item_id) >= count.unwrap_or(1)
+            }
+            LevelCondition::HasSkill { skill_id } => player_state.has_skill(*skill_id),
+            LevelCondition::HasSkillType { skill_type } => {
+                player_state.has_skill_type(*skill_type, &player_data.skills)
+            }
+            LevelCondition::HpBelow { ratio } => {
+                let (hp, max_hp) = player_state.get_hp();
+                (hp as f64) < max_hp as f64 * ratio
+            }
+            LevelCondition::FlagSet { flag } => player_state.is_flag_set(flag),
+            LevelCondition::CounterAtLeast { counter, value } => {
+                player_state.counter(counter) >= *value
+            }
+            LevelCondition::CounterBelow { counter, value } => {
+                player_state.counter(counter) < *value
+            }
+            LevelCondition::Time { time } => self.current_time == *time,
+            LevelCondition::DepthAtLeast { depth } => self.current_depth.depth() >= *depth,
+            LevelCondition::DepthBelow { depth } => self.current_depth.depth() < *depth,
+            LevelCondition::Not { condition } => {
+                !self.check_condition(condition, player_state, player_data)
+            }
+            LevelCondition::All { conditions } => conditions
+                .iter()
+                .all(|c| self.check_condition(c, player_state, player_data)),
+            LevelCondition::Any { conditions } => conditions
+                .iter()
+                .any(|c| self.check_condition(c, player_state, player_data)),
+        }
+    }
+
     #[async_recursion(?Send)]
     async fn process_event(
         &mut self,
-        rng: &mut ThreadRng,
+        rng: &mut StdRng,
         player_state: &mut PlayerState,
         save_data: &mut save_data::SaveData,
         player_data: &PlayerData,
@@ -163,13 +357,31 @@ impl<'a> ExploreScene<'a> {
                             level_item,
                         )
                         .await;
-                    if let ProcessEventResult::GameOver = result {
-                        return ProcessEventResult::GameOver;
+                    if !matches!(result, ProcessEventResult::Playing) {
+                        return result;
                     }
                 }
             }
             LevelItem::Random { branches } => {
-                let dist = WeightedIndex::new(branches.iter().map(|b| b.weight)).unwrap();
+                let weights = branches.iter().map(|b| {
+                    if let Some(condition) = &b.condition {
+                        if !self.check_condition(condition, player_state, player_data) {
+                            return 0.0;
+                        }
+                    }
+                    let counter_weight = b.counter_weight.as_ref().map_or(0.0, |cw| {
+                        player_state.counter(&cw.counter) as f64 * cw.weight
+                    });
+                    (b.weight + counter_weight).max(0.0)
+                });
+                // 条件によってすべての分岐が選べない場合は何も起こらない
+                let dist = match WeightedIndex::new(weights) {
+                    Ok(dist) => dist,
+                    Err(_) => {
+                        trace!("No available random level branch");
+                        return ProcessEventResult::Playing;
+                    }
+                };
                 let index = dist.sample(rng);
 
                 trace!("Random level index: {}", index);
@@ -189,13 +401,21 @@ impl<'a> ExploreScene<'a> {
             }
             LevelItem::Message {
                 text,
+                args,
                 blue,
                 no_weight,
             } => {
+                let format_args = args
+                    .as_ref()
+                    .map_or(vec![], |args| message_args(args, player_state));
                 if blue.is_some() && blue.unwrap() {
-                    self.message_list.add_normal_blue_message(text).await;
+                    self.message_list
+                        .add_normal_blue_message(text, format_args)
+                        .await;
                 } else {
-                    self.message_list.add_normal_message(text).await;
+                    self.message_list
+                        .add_normal_message(text, format_args)
+                        .await;
                 }
                 if !(no_weight.is_some() && no_weight.unwrap()) {
                     self.wait_move_forward(player_state, player_data, item_data, save_data)
@@ -205,7 +425,14 @@ impl<'a> ExploreScene<'a> {
             LevelItem::Choice { text, branches } => {
                 let choices = &branches
                     .iter()
-                    .map(|b| (b.text_lines, b.text.as_str()))
+                    .map(|b| {
+                        let enabled = b
+                            .condition
+                            .iter()
+                            .all(|c| self.check_condition(c, player_state, player_data))
+                            && player_state.can_pay(b.costs.as_deref().unwrap_or(&[]));
+                        (b.text_lines, b.text.as_str(), enabled)
+                    })
                     .collect::<Vec<_>>();
                 let index = self
                     .wait_choice(
@@ -220,6 +447,13 @@ impl<'a> ExploreScene<'a> {
 
                 trace!("Choice level index: {}", index);
 
+                if let Some(costs) = &branches[index].costs {
+                    player_state
+                        .pay(costs)
+                        .expect("Selected choice must be affordable");
+                    self.player_status.update(player_state);
+                }
+
                 return self
                     .process_event(
                         rng,
@@ -232,6 +466,34 @@ impl<'a> ExploreScene<'a> {
                     )
                     .await;
             }
+            LevelItem::If {
+                condition,
+                then,
+                otherwise,
+            } => {
+                let result = self.check_condition(condition, player_state, player_data);
+
+                trace!("If level condition: {}", result);
+
+                let level_item = if result {
+                    Some(then)
+                } else {
+                    otherwise.as_ref()
+                };
+                if let Some(level_item) = level_item {
+                    return self
+                        .process_event(
+                            rng,
+                            player_state,
+                            save_data,
+                            player_data,
+                            item_data,
+                            game_data,
+                            level_item,
+                        )
+                        .await;
+                }
+            }
             LevelItem::PlayBGM { bgm } => {
                 self.cx.play_bgm(bgm);
                 self.current_explore_bgm = bgm.to_string();
@@ -241,30 +503,107 @@ impl<'a> ExploreScene<'a> {
                 self.cx.resume_or_play_bgm(bgm);
                 self.current_explore_bgm = bgm.to_string();
             }
-            LevelItem::ChangeToAfternoon => self.background.change_to_afternoon().await,
-            LevelItem::ChangeToNight => self.background.change_to_night().await,
-            LevelItem::Battle { id: _id, bgm, time } => {
-                // let battle_data = &game_data.battle_data[id];
-                self.cx.play_bgm(bgm);
+            LevelItem::ChangeToAfternoon => {
+                self.background.change_to_afternoon().await;
+                self.current_time = BattleTime::Afternoon;
+            }
+            LevelItem::ChangeToNight => {
+                self.background.change_to_night().await;
+                self.current_time = BattleTime::Night;
+            }
+            LevelItem::Battle {
+                id,
+                bgm,
+                time,
+                companions,
+                escape_penalty,
+            } => {
+                let encounter = Encounter {
+                    enemy_data: std::iter::once(id)
+                        .chain(companions.iter().flatten())
+                        .map(|id| {
+                            game_data
+                                .enemy_data()
+                                .iter()
+                                .find(|e| e.id == *id)
+                                .unwrap_or_else(|| panic!("No enemy data. enemy id: {:?}", id))
+                        })
+                        .collect(),
+                    time: *time,
+                };
+                self.cx.play_bgm(
+                    encounter
+                        .enemy_data
+                        .iter()
+                        .find_map(|e| e.bgm.as_ref())
+                        .unwrap_or(bgm),
+                );
+                let enemy_ids = encounter
+                    .enemy_data
+                    .iter()
+                    .map(|e| e.id)
+                    .collect::<Vec<_>>();
+                if let Err(e) = save_data.record_encounter(&enemy_ids) {
+                    warn!("Failed to save encounter: {}", e);
+                }
                 self.cover.start_battle().await;
                 let result = battle(
                     self.cx,
-                    // battle_data,
                     self.player_index,
                     player_data,
                     item_data,
+                    &encounter,
                     player_state,
-                    *time,
+                    rng,
                 )
                 .await;
+                self.player_status.update(player_state);
                 match result {
-                    BattleResult::Win => {
+                    BattleResult::Win(rewards) => {
+                        if let Err(e) = save_data.record_defeat(&enemy_ids) {
+                            warn!("Failed to save defeat: {}", e);
+                        }
+                        rewards.apply(player_state);
+                        self.player_status.update(player_state);
                         self.cx.resume_or_play_bgm(&self.current_explore_bgm);
                         self.cover.fade_in().await;
+                        if !rewards.skill_candidates.is_empty() {
+                            self.wait_add_skill(
+                                &rewards.skill_candidates,
+                                player_state,
+                                player_data,
+                            )
+                            .await;
+                        }
                         return ProcessEventResult::Playing;
                     }
-                    BattleResult::Lose => {
-                        return ProcessEventResult::GameOver;
+                    BattleResult::Lose(enemy_id) => {
+                        let enemy = encounter
+                            .enemy_data
+                            .iter()
+                            .find(|e| e.id == enemy_id)
+                            .unwrap();
+                        return ProcessEventResult::GameOver {
+                            killed_by: Some(enemy.enemy_name.clone()),
+                        };
+                    }
+                    BattleResult::Escaped => {
+                        self.cx.resume_or_play_bgm(&self.current_explore_bgm);
+                        self.cover.fade_in().await;
+                        if let Some(penalty) = escape_penalty {
+                            return self
+                                .process_event(
+                                    rng,
+                                    player_state,
+                                    save_data,
+                                    player_data,
+                                    item_data,
+                                    game_data,
+                                    penalty,
+                                )
+                                .await;
+                        }
+                        return ProcessEventResult::Playing;
                     }
                 }
             }
@@ -278,27 +617,8 @@ impl<'a> ExploreScene<'a> {
                 .await;
             }
             LevelItem::GetSkill { skills, count } => {
-                let mut list = skills
-                    .iter()
-                    .flat_map(|sr| {
-                        let rarity = sr.rarity;
-                        let weight = sr.weight;
-                        player_data
-                            .skills
-                            .iter()
-                            .filter(move |s| s.rarity == rarity)
-                            .map(move |s| (s.id, s.rarity_weight * weight))
-                    })
-                    .filter(|(s, _)| !player_state.get_skills().contains(s))
-                    .collect::<Vec<_>>();
-                let mut candidate_skills = vec![];
-                for _ in 0..(list.len().min(*count as usize)) {
-                    let dist = WeightedIndex::new(list.iter().map(|(_, w)| w)).unwrap();
-                    let index = dist.sample(rng);
-                    let (skill_id, _) = list.remove(index);
-                    candidate_skills.push(skill_id);
-                }
-                candidate_skills.sort_by_key(|s| s.0);
+                let candidate_skills =
+                    player_state.pick_skill_candidates(skills, *count, &player_data.skills, rng);
                 self.wait_add_skill(&candidate_skills, player_state, player_data)
                     .await;
             }
@@ -307,35 +627,222 @@ impl<'a> ExploreScene<'a> {
                     player_state.add_item(*item_id);
                 }
             }
+            LevelItem::RemoveItem { item_id, count } => {
+                let removed = player_state.remove_item(*item_id, *count as u32);
+
+                trace!("Remove item: {:?}, {}/{}", item_id, removed, count);
+            }
+            LevelItem::Trade {
+                give,
+                receive,
+                then,
+                otherwise,
+            } => {
+                let level_item = match player_state.pay(give) {
+                    Ok(()) => {
+                        for item in receive.iter() {
+                            for _ in 0..item.count {
+                                player_state.add_item(item.item_id);
+                            }
+                        }
+                        self.player_status.update(player_state);
+                        then.as_ref()
+                    }
+                    Err(err) => {
+                        trace!("Trade failed: {}", err);
+                        otherwise.as_ref()
+                    }
+                };
+                if let Some(level_item) = level_item {
+                    return self
+                        .process_event(
+                            rng,
+                            player_state,
+                            save_data,
+                            player_data,
+                            item_data,
+                            game_data,
+                            level_item,
+                        )
+                        .await;
+                }
+            }
+            LevelItem::JumpTo { level } => {
+                let index = game_data
+                    .level_index(level)
+                    .unwrap_or_else(|| panic!("No level data. level id: {}", level));
+                return ProcessEventResult::JumpTo(index);
+            }
+            LevelItem::Skip { count } => return ProcessEventResult::Skip(*count),
+            LevelItem::Heal { amount } => {
+                self.cx.play_sfx("/audio/sfx/heal.ogg");
+                player_state.heal(*amount);
+                self.player_status.update(player_state);
+            }
+            LevelItem::Damage { amount } => {
+                self.cx.play_sfx("/audio/sfx/hit-1.ogg");
+                player_state.damage(*amount);
+                self.player_status.update(player_state);
+                if player_state.get_hp().0 == 0 {
+                    self.cover.fade_out().await;
+                    return ProcessEventResult::GameOver { killed_by: None };
+                }
+            }
+            LevelItem::RestoreTp { amount } => {
+                self.cx.play_sfx("/audio/sfx/heal.ogg");
+                player_state.restore_tp(*amount);
+                self.player_status.update(player_state);
+            }
+            LevelItem::SetFlag { flag, scope } => {
+                player_state.set_flag(flag);
+                if *scope == Some(FlagScope::Persistent) {
+                    player_state.mark_persistent_flag(flag);
+                    if let Err(e) = save_data.set_flag(flag) {
+                        warn!("Failed to save flag: {}", e);
+                    }
+                }
+            }
+            LevelItem::ClearFlag { flag, scope } => {
+                player_state.clear_flag(flag);
+                if *scope == Some(FlagScope::Persistent) {
+                    player_state.mark_persistent_flag(flag);
+                    if let Err(e) = save_data.clear_flag(flag) {
+                        warn!("Failed to save flag: {}", e);
+                    }
+                }
+            }
+            LevelItem::AddCounter {
+                counter,
+                amount,
+                scope,
+            } => {
+                player_state.add_counter(counter, *amount);
+                if *scope == Some(FlagScope::Persistent) {
+                    player_state.mark_persistent_counter(counter);
+                    if let Err(e) = save_data.add_counter(counter, *amount) {
+                        warn!("Failed to save counter: {}", e);
+                    }
+                }
+            }
         }
         ProcessEventResult::Playing
     }
 
-    pub(super) async fn start(&mut self, global_data: &mut game::GlobalData) -> ExploreResult {
+    fn checkpoint(&self, level_index: usize, player_state: &PlayerState) -> Checkpoint {
+        Checkpoint {
+            level_index,
+            depth: self.current_depth.depth(),
+            player_state: player_state.clone(),
+            explore_bgm: self.current_explore_bgm.clone(),
+            time: self.current_time,
+            last_checkpoint: None,
+        }
+    }
+
+    /// 次のレベルに入る時点の状態を保存する。再開後も同じ展開になるよう乱数を保存した種で初期化し直す
+    fn save_suspend_data(
+        &self,
+        rng: &mut StdRng,
+        save_data: &mut save_data::SaveData,
+        level_index: usize,
+        player_state: &PlayerState,
+        last_checkpoint: Option<&Checkpoint>,
+    ) {
+        let rng_seed = rng.gen();
+        *rng = StdRng::seed_from_u64(rng_seed);
+        // 保存に失敗しても探索は続けられるので、ログだけ残す
+        if let Err(e) = save_data.set_suspend_data(save_data::SuspendData {
+            player_index: self.player_index,
+            run_seed: self.run_seed,
+            rng_seed,
+            state: self.checkpoint(level_index, player_state).to_run_state(),
+            checkpoint: last_checkpoint.map(Checkpoint::to_run_state),
+        }) {
+            warn!("Failed to save suspend data: {}", e);
+        }
+    }
+
+    fn record_run(
+        &self,
+        save_data: &mut save_data::SaveData,
+        player_state: &PlayerState,
+        max_depth: u32,
+        result: save_data::RunResult,
+    ) {
+        let (battle_count, turn_count) = player_state.get_battle_stats();
+        if let Err(e) = save_data.add_run_record(save_data::RunRecord {
+            player_index: self.player_index,
+            seed: self.run_seed,
+            depth: self.current_depth.depth(),
+            max_depth,
+            result,
+            skills: player_state.get_skills(),
+            battle_count,
+            turn_count,
+            timestamp: chrono::Local::now().timestamp(),
+        }) {
+            warn!("Failed to save run record: {}", e);
+        }
+    }
+
+    pub(super) async fn start(
+        &mut self,
+        global_data: &mut game::GlobalData,
+        checkpoint: Option<Checkpoint>,
+    ) -> ExploreResult {
         let rng = &mut *global_data.rng.borrow_mut();
         let game_data = &global_data.game_data;
         let save_data = &mut global_data.save_data;
         let player_data = &game_data.player_data()[self.player_index];
         let item_data = game_data.item_data();
         let level_data = game_data.level_data();
+        let max_depth = game_data.max_depth();
 
-        let mut player_state = PlayerState::new();
+        let mut last_checkpoint = None;
+        let (mut index, depth, mut player_state) = match checkpoint {
+            Some(checkpoint) => {
+                self.current_explore_bgm = checkpoint.explore_bgm;
+                self.current_time = checkpoint.time;
+                self.background.set_time(checkpoint.time);
+                last_checkpoint = checkpoint.last_checkpoint.map(|c| *c);
+                (
+                    checkpoint.level_index,
+                    checkpoint.depth,
+                    checkpoint.player_state,
+                )
+            }
+            None => (0, 1, PlayerState::new(player_data)),
+        };
         let player_state = &mut player_state;
+        player_state.restore_persistent(save_data.flags(), save_data.counters());
+        self.player_status.update(player_state);
 
-        self.current_depth.set_max_depth(level_data.len());
+        self.current_depth.set_max_depth(max_depth as usize);
+        self.current_depth.set_depth(depth);
         self.cx.play_bgm(&self.current_explore_bgm);
         self.cover.fade_in().await;
 
-        for (
-            index,
-            LevelData {
-                item: level_item, ..
-            },
-        ) in level_data.iter().enumerate()
-        {
-            info!("Level index: {}", index + 1);
+        loop {
+            let LevelData {
+                item: level_item,
+                checkpoint,
+                next,
+                ..
+            } = &level_data[index];
+
+            info!(
+                "Level index: {}, depth: {}",
+                index + 1,
+                self.current_depth.depth()
+            );
+
+            if *checkpoint == Some(true) {
+                info!("Checkpoint: {}", index + 1);
+                last_checkpoint = Some(self.checkpoint(index, player_state));
+            }
 
-            match self
+            // 次に進むレベル。Noneならゲームクリア
+            let next_index = match self
                 .process_event(
                     rng,
                     player_state,
@@ -347,22 +854,75 @@ impl<'a> ExploreScene<'a> {
                 )
                 .await
             {
-                ProcessEventResult::Playing => {
-                    if index + 1 == level_data.len() {
-                        self.cover.fade_out().await;
-                        return ExploreResult::GameClear;
+                ProcessEventResult::Playing => match next {
+                    Some(next) => Some(
+                        game_data
+                            .level_index(next)
+                            .unwrap_or_else(|| panic!("No level data. level id: {}", next)),
+                    ),
+                    None => Some(index + 1),
+                },
+                ProcessEventResult::JumpTo(next_index) => Some(next_index),
+                // 最後のレベルを越えるSkipはデータの誤りなので、クリア扱いにせず次のレベルに進む
+                ProcessEventResult::Skip(count) if index + 1 + count < level_data.len() => {
+                    Some(index + 1 + count)
+                }
+                ProcessEventResult::Skip(count) => {
+                    warn!(
+                        "Skip past the last level. level index: {}, count: {}",
+                        index, count
+                    );
+                    Some(index + 1)
+                }
+                ProcessEventResult::GameOver { killed_by } => {
+                    if let Err(e) = save_data.clear_suspend_data() {
+                        warn!("Failed to clear suspend data: {}", e);
                     }
+                    let result = match &killed_by {
+                        Some(enemy_name) => save_data::RunResult::KilledBy {
+                            enemy_name: enemy_name.clone(),
+                        },
+                        None => save_data::RunResult::Exhausted,
+                    };
+                    self.record_run(save_data, player_state, max_depth, result);
+                    return ExploreResult::GameOver(Box::new(GameOverSummary {
+                        depth: self.current_depth.depth(),
+                        max_depth,
+                        killed_by,
+                        checkpoint: last_checkpoint,
+                    }));
+                }
+            }
+            .filter(|&next_index| next_index < level_data.len());
 
+            match next_index {
+                Some(next_index) => {
+                    index = next_index;
                     self.cx.play_sfx("/audio/sfx/footstep.ogg");
                     self.message_list.add_space().await;
                     self.current_depth.increment();
+                    self.save_suspend_data(
+                        rng,
+                        save_data,
+                        index,
+                        player_state,
+                        last_checkpoint.as_ref(),
+                    );
                 }
-                ProcessEventResult::GameOver => {
-                    return ExploreResult::GameOver;
+                None => {
+                    if let Err(e) = save_data.clear_suspend_data() {
+                        warn!("Failed to clear suspend data: {}", e);
+                    }
+                    self.record_run(
+                        save_data,
+                        player_state,
+                        max_depth,
+                        save_data::RunResult::Clear,
+                    );
+                    self.cover.fade_out().await;
+                    return ExploreResult::GameClear;
                 }
             }
         }
-
-        unreachable!()
     }
 }
@@ -10,6 +10,7 @@ use crate::input;
 const MESSAGE_LIST_ITEM_SPACE: f32 = 15.0;
 const MESSAGE_CHOICE_ITEM_SPACE: f32 = 5.0;
 const MESSAGE_SPACE_HEIGHT: f32 = 65.0;
+const DISABLED_CHOICE_OPACITY: f32 = 0.3;
 
 enum MessageListItem<'a> {
     NormalMessage {
@@ -27,11 +28,16 @@ enum MessageListItem<'a> {
         choice_windows: Vec<Entity>,
         choice_window_highlights: Vec<Entity>,
         choice_window_heights: Vec<f32>,
+        choice_enabled: Vec<bool>,
         cursor: Entity,
     },
 }
 impl<'a> MessageListItem<'a> {
-    fn new_normal(cx: &'a AnimationEngineContext, message: impl ToString) -> Self {
+    fn new_normal(
+        cx: &'a AnimationEngineContext,
+        message: impl ToString,
+        format_args: Vec<String>,
+    ) -> Self {
         trace!("NormalMessage: {}", message.to_string());
         let window = cx.add_image(AddImageInfo {
             name: "/image/ui/explore-part-11.png".into(),
@@ -40,6 +46,7 @@ impl<'a> MessageListItem<'a> {
         });
         let text = cx.add_text(AddTextInfo {
             key: message.to_string(),
+            format_args,
             font_size: 24.0,
             z: 50,
             ..Default::default()
@@ -52,7 +59,11 @@ impl<'a> MessageListItem<'a> {
         }
     }
 
-    fn new_normal_blue(cx: &'a AnimationEngineContext, message: impl ToString) -> Self {
+    fn new_normal_blue(
+        cx: &'a AnimationEngineContext,
+        message: impl ToString,
+        format_args: Vec<String>,
+    ) -> Self {
         trace!("NormalBlueMessage: {}", message.to_string());
         let window = cx.add_image(AddImageInfo {
             name: "/image/ui/explore-part-11.png".into(),
@@ -61,6 +72,7 @@ impl<'a> MessageListItem<'a> {
         });
         let text = cx.add_text(AddTextInfo {
             key: message.to_string(),
+            format_args,
             font_size: 24.0,
             z: 50,
             r: 0.3,
@@ -79,14 +91,14 @@ impl<'a> MessageListItem<'a> {
     fn new_choice(
         cx: &'a AnimationEngineContext,
         message: impl ToString,
-        choices: &[(u8, impl ToString)],
+        choices: &[(u8, impl ToString, bool)],
     ) -> Self {
         trace!(
             "ChoiceMessage: {}, choices: {:?}",
             message.to_string(),
             choices
                 .iter()
-                .map(|(lines, item)| (lines, item.to_string()))
+                .map(|(lines, item, enabled)| (lines, item.to_string(), enabled))
                 .collect::<Vec<_>>()
         );
         let window = cx.add_image(AddImageInfo {
@@ -104,11 +116,18 @@ impl<'a> MessageListItem<'a> {
         let mut choice_windows = vec![];
         let mut choice_window_highlights = vec![];
         let mut choice_window_heights = vec![];
-        for (lines, choice) in choices {
+        let mut choice_enabled = vec![];
+        for (lines, choice, enabled) in choices {
+            let a = if *enabled {
+                1.0
+            } else {
+                DISABLED_CHOICE_OPACITY
+            };
             let text = cx.add_text(AddTextInfo {
                 key: choice.to_string(),
                 font_size: 24.0,
                 z: 55,
+                a,
                 ..Default::default()
             });
             let (window_img, window_highlight_img, height) = match lines {
@@ -132,6 +151,7 @@ impl<'a> MessageListItem<'a> {
             let window = cx.add_image(AddImageInfo {
                 name: window_img.into(),
                 z: 40,
+                a,
                 ..Default::default()
             });
             let window_highlight = cx.add_image(AddImageInfo {
@@ -144,6 +164,7 @@ impl<'a> MessageListItem<'a> {
             choice_windows.push(window);
             choice_window_highlights.push(window_highlight);
             choice_window_heights.push(height);
+            choice_enabled.push(*enabled);
         }
         let cursor = cx.add_image(AddImageInfo {
             name: "/image/ui/explore-part-10.png".into(),
@@ -160,6 +181,7 @@ impl<'a> MessageListItem<'a> {
             choice_windows,
             choice_window_highlights,
             choice_window_heights,
+            choice_enabled,
             cursor,
         }
     }
@@ -250,17 +272,22 @@ impl<'a> MessageListItem<'a> {
                 text,
                 choice_texts,
                 choice_windows,
-                choice_window_heights,
+                choice_enabled,
                 ..
             } => {
                 cx.set_opacity(*window, 0.6)
                     .expect("Failed to set message window opacity");
                 cx.set_opacity(*text, 0.6)
                     .expect("Failed to set message window opacity");
-                for (i, _) in choice_window_heights.iter().enumerate() {
-                    cx.set_opacity(choice_windows[i], 0.6)
+                for (i, &enabled) in choice_enabled.iter().enumerate() {
+                    let opacity = if enabled {
+                        0.6
+                    } else {
+                        DISABLED_CHOICE_OPACITY
+                    };
+                    cx.set_opacity(choice_windows[i], opacity)
                         .expect("Failed to set message window opacity");
-                    cx.set_opacity(choice_texts[i].1, 0.6)
+                    cx.set_opacity(choice_texts[i].1, opacity)
                         .expect("Failed to set message window opacity");
                 }
             }
@@ -291,12 +318,13 @@ impl<'a> MessageListItem<'a> {
                 choice_texts,
                 choice_window_heights,
                 choice_window_highlights,
+                choice_enabled,
                 cursor,
                 ..
             } => {
                 cx.set_opacity(*cursor, 1.0).unwrap();
                 let len = choice_window_heights.len();
-                let mut index = 0;
+                let mut index = choice_enabled.iter().position(|&e| e).unwrap_or(0);
                 loop {
                     for (i, &entity) in choice_window_highlights.iter().enumerate() {
                         if i == index {
@@ -328,6 +356,12 @@ impl<'a> MessageListItem<'a> {
                             delay(Duration::from_millis(150)).await;
                         }
                         _ = input::wait_select_button(cx).fuse() => {
+                            // 選べない選択肢はカーソルを合わせられるが決定できない
+                            if !choice_enabled[index] {
+                                cx.play_sfx("/audio/sfx/cancel.ogg");
+                                delay(Duration::from_millis(150)).await;
+                                continue;
+                            }
                             cx.play_sfx("/audio/sfx/select.ogg");
                             cx.set_opacity(*cursor, 0.0).unwrap();
                             for (i, &highlight) in choice_window_highlights.iter().enumerate() {
@@ -391,11 +425,15 @@ impl<'a> MessageList<'a> {
         Self { cx, items }
     }
 
-    pub(super) async fn add_normal_message(&mut self, message: impl ToString) {
+    pub(super) async fn add_normal_message(
+        &mut self,
+        message: impl ToString,
+        format_args: Vec<String>,
+    ) {
         self.cx.play_sfx("/audio/sfx/cursor.ogg");
         self.items.iter().for_each(|item| item.change_opacity());
 
-        let message = MessageListItem::new_normal(self.cx, message);
+        let message = MessageListItem::new_normal(self.cx, message, format_args);
         let height = message.get_height();
         self.items.push_front(message);
         if self.items.len() > 9 {
@@ -410,11 +448,15 @@ impl<'a> MessageList<'a> {
         }
     }
 
-    pub(super) async fn add_normal_blue_message(&mut self, message: impl ToString) {
+    pub(super) async fn add_normal_blue_message(
+        &mut self,
+        message: impl ToString,
+        format_args: Vec<String>,
+    ) {
         self.cx.play_sfx("/audio/sfx/cursor.ogg");
         self.items.iter().for_each(|item| item.change_opacity());
 
-        let message = MessageListItem::new_normal_blue(self.cx, message);
+        let message = MessageListItem::new_normal_blue(self.cx, message, format_args);
         let height = message.get_height();
         self.items.push_front(message);
         if self.items.len() > 9 {
@@ -432,7 +474,7 @@ impl<'a> MessageList<'a> {
     pub(super) async fn add_choice_message(
         &mut self,
         message: impl ToString,
-        choices: &[(u8, impl ToString)],
+        choices: &[(u8, impl ToString, bool)],
     ) {
         self.cx.play_sfx("/audio/sfx/cursor.ogg");
         self.items.iter().for_each(|item| item.change_opacity());
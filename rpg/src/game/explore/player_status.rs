@@ -0,0 +1,59 @@
+use animation_engine::*;
+
+use crate::game::*;
+
+pub(super) struct PlayerStatus<'a> {
+    cx: &'a AnimationEngineContext,
+    hp_text: Entity,
+    tp_text: Entity,
+}
+impl<'a> PlayerStatus<'a> {
+    pub(super) fn new(cx: &'a AnimationEngineContext) -> Self {
+        let hp_text = cx.add_text(AddTextInfo {
+            key: "explore-hp".into(),
+            font_size: 28.0,
+            format_args: vec!["  0".into(), "0".into()],
+            x: 70.0,
+            y: 150.0,
+            z: 20,
+            ..Default::default()
+        });
+        let tp_text = cx.add_text(AddTextInfo {
+            key: "explore-tp".into(),
+            font_size: 28.0,
+            format_args: vec!["  0".into(), "0".into()],
+            x: 70.0,
+            y: 190.0,
+            z: 20,
+            ..Default::default()
+        });
+        Self {
+            cx,
+            hp_text,
+            tp_text,
+        }
+    }
+
+    pub(super) fn update(&self, player_state: &PlayerState) {
+        let (hp, max_hp) = player_state.get_hp();
+        let (tp, max_tp) = player_state.get_tp();
+        self.cx
+            .set_text_format_args(
+                self.hp_text,
+                &[&format!("{:4}", hp), &format!("{}", max_hp)],
+            )
+            .unwrap();
+        self.cx
+            .set_text_format_args(
+                self.tp_text,
+                &[&format!("{:4}", tp), &format!("{}", max_tp)],
+            )
+            .unwrap();
+    }
+}
+impl<'a> Drop for PlayerStatus<'a> {
+    fn drop(&mut self) {
+        self.cx.delete_entity(self.hp_text);
+        self.cx.delete_entity(self.tp_text);
+    }
+}
@@ -1,7 +1,7 @@
 use animation_engine::executor::*;
 use animation_engine::*;
 use futures::Future;
-use log::{info, trace};
+use log::{info, trace, warn};
 use rand::prelude::*;
 use std::cell::RefCell;
 use std::pin::Pin;
@@ -11,7 +11,7 @@ use crate::game_data;
 use crate::save_data;
 
 pub struct GlobalData {
-    pub rng: RefCell<ThreadRng>,
+    pub rng: RefCell<StdRng>,
     cx: AnimationEngineContext,
     overlay_image: Entity,
     pub save_data: save_data::SaveData,
@@ -21,7 +21,7 @@ impl GlobalData {
     pub fn load(engine: &mut AnimationEngine) -> anyhow::Result<Self> {
         trace!("Create global data");
 
-        let rng = RefCell::new(rand::thread_rng());
+        let rng = RefCell::new(StdRng::from_entropy());
 
         let cx = engine.get_context().clone();
 
@@ -58,16 +58,48 @@ async fn main(cx: AnimationEngineContext, mut global_data: GlobalData) {
     global_data.save_data.apply(&cx);
 
     loop {
-        match title::title(&cx, &mut global_data).await {
+        let (index, seed, mut checkpoint) = match title::title(&cx, &mut global_data).await {
             title::TitleResult::Exit => break,
-            title::TitleResult::StartGame => {
+            title::TitleResult::StartGame(seed) => {
+                *global_data.rng.borrow_mut() = StdRng::seed_from_u64(seed);
                 let opening::PlayerIndex(index) = opening::opening(&cx, &mut global_data).await;
-                match explore::explore(&cx, &mut global_data, index).await {
-                    explore::ExploreResult::GameClear => {
-                        crate::input::wait_select_button(&cx).await;
+                if let Err(e) = global_data.save_data.clear_suspend_data() {
+                    warn!("Failed to clear suspend data: {}", e);
+                }
+                (index, Some(seed), None)
+            }
+            title::TitleResult::Continue => {
+                let suspend = global_data
+                    .save_data
+                    .suspend_data()
+                    .expect("No suspend data")
+                    .clone();
+                match explore::Checkpoint::from_suspend_data(&suspend, &global_data.game_data) {
+                    Some(checkpoint) => {
+                        *global_data.rng.borrow_mut() = StdRng::seed_from_u64(suspend.rng_seed);
+                        (suspend.player_index, suspend.run_seed, Some(checkpoint))
+                    }
+                    None => {
+                        warn!("Suspend data does not match game data. Discard it.");
+                        if let Err(e) = global_data.save_data.clear_suspend_data() {
+                            warn!("Failed to clear suspend data: {}", e);
+                        }
+                        continue;
                     }
-                    explore::ExploreResult::GameOver => {
-                        crate::input::wait_select_button(&cx).await;
+                }
+            }
+        };
+        loop {
+            match explore::explore(&cx, &mut global_data, index, seed, checkpoint.take()).await {
+                explore::ExploreResult::GameClear => {
+                    ending::ending(&cx, &global_data, index).await;
+                    credits::credits(&cx, &global_data).await;
+                    break;
+                }
+                explore::ExploreResult::GameOver(summary) => {
+                    match game_over::game_over(&cx, &summary).await {
+                        game_over::GameOverResult::Retry => checkpoint = summary.checkpoint,
+                        game_over::GameOverResult::ReturnToTitle => break,
                     }
                 }
             }
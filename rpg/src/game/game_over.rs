@@ -0,0 +1,211 @@
+use animation_engine::executor::*;
+use animation_engine::*;
+use futures::{select, try_join, FutureExt};
+use log::{info, trace};
+use std::time::Duration;
+
+use crate::game::explore::GameOverSummary;
+use crate::input;
+use crate::localization;
+
+pub enum GameOverResult {
+    Retry,
+    ReturnToTitle,
+}
+
+struct GameOverScene<'a> {
+    cx: &'a AnimationEngineContext,
+    cover: Entity,
+    text_title: Entity,
+    text_depth: Entity,
+    text_cause: Entity,
+    text_retry: Entity,
+    text_return_to_title: Entity,
+}
+impl<'a> GameOverScene<'a> {
+    fn new(cx: &'a AnimationEngineContext, summary: &GameOverSummary) -> Self {
+        let cover = cx.add_rect(AddRectInfo {
+            width: 1280.0,
+            height: 720.0,
+            r: 0.0,
+            g: 0.0,
+            b: 0.0,
+            a: 0.0,
+            z: 400,
+            ..Default::default()
+        });
+        let text_title = cx.add_text(AddTextInfo {
+            key: "game-over-title".into(),
+            font_size: 72.0,
+            x: 440.0,
+            y: 140.0,
+            z: 410,
+            a: 0.0,
+            ..Default::default()
+        });
+        let text_depth = cx.add_text(AddTextInfo {
+            key: "game-over-depth".into(),
+            format_args: vec![summary.depth.to_string(), summary.max_depth.to_string()],
+            font_size: 30.0,
+            x: 440.0,
+            y: 270.0,
+            z: 410,
+            a: 0.0,
+            ..Default::default()
+        });
+        let text_cause = match &summary.killed_by {
+            Some(enemy_name) => cx.add_text(AddTextInfo {
+                key: "game-over-killed-by".into(),
+                format_args: vec![localization::get_text(enemy_name)],
+                font_size: 30.0,
+                x: 440.0,
+                y: 320.0,
+                z: 410,
+                a: 0.0,
+                ..Default::default()
+            }),
+            None => cx.add_text(AddTextInfo {
+                key: "game-over-exhausted".into(),
+                font_size: 30.0,
+                x: 440.0,
+                y: 320.0,
+                z: 410,
+                a: 0.0,
+                ..Default::default()
+            }),
+        };
+        let text_retry = cx.add_text(AddTextInfo {
+            key: if summary.checkpoint.is_some() {
+                "game-over-retry-checkpoint".into()
+            } else {
+                "game-over-retry-start".into()
+            },
+            font_size: 36.0,
+            x: 440.0,
+            y: 450.0,
+            z: 410,
+            r: 212.0 / 255.0,
+            g: 1.0,
+            b: 1.0,
+            a: 0.0,
+            ..Default::default()
+        });
+        let text_return_to_title = cx.add_text(AddTextInfo {
+            key: "game-over-return-to-title".into(),
+            font_size: 36.0,
+            x: 440.0,
+            y: 510.0,
+            z: 410,
+            r: 212.0 / 255.0,
+            g: 1.0,
+            b: 1.0,
+            a: 0.0,
+            ..Default::default()
+        });
+        Self {
+            cx,
+            cover,
+            text_title,
+            text_depth,
+            text_cause,
+            text_retry,
+            text_return_to_title,
+        }
+    }
+
+    async fn start(&self) -> GameOverResult {
+        self.cx.play_bgm("game-over");
+
+        trace!("start game over enter animation");
+
+        self.cx
+            .play_animation(self.cover, "/animation/game-over/cover-enter.yml")
+            .await
+            .expect("animation not found");
+        try_join!(
+            self.cx
+                .play_animation(self.text_title, "/animation/game-over/content-enter.yml"),
+            self.cx
+                .play_animation(self.text_depth, "/animation/game-over/content-enter.yml"),
+            self.cx
+                .play_animation(self.text_cause, "/animation/game-over/content-enter.yml"),
+            self.cx
+                .play_animation(self.text_retry, "/animation/game-over/content-enter.yml"),
+            self.cx.play_animation(
+                self.text_return_to_title,
+                "/animation/game-over/content-enter.yml"
+            ),
+        )
+        .expect("animation not found");
+
+        let mut index = 0;
+        let result = loop {
+            self.cx
+                .set_opacity(self.text_retry, if index == 0 { 1.0 } else { 0.2 })
+                .unwrap();
+            self.cx
+                .set_opacity(
+                    self.text_return_to_title,
+                    if index == 1 { 1.0 } else { 0.2 },
+                )
+                .unwrap();
+            select! {
+                _ = input::wait_up(self.cx).fuse() => {
+                    index = (index + 1) % 2;
+                    self.cx.play_sfx("/audio/sfx/cursor.ogg");
+                    delay(Duration::from_millis(150)).await;
+                }
+                _ = input::wait_down(self.cx).fuse() => {
+                    index = (index + 1) % 2;
+                    self.cx.play_sfx("/audio/sfx/cursor.ogg");
+                    delay(Duration::from_millis(150)).await;
+                }
+                _ = input::wait_select_button(self.cx).fuse() => {
+                    self.cx.play_sfx("/audio/sfx/select.ogg");
+                    break if index == 0 {
+                        GameOverResult::Retry
+                    } else {
+                        GameOverResult::ReturnToTitle
+                    };
+                }
+            }
+            next_frame().await;
+        };
+
+        trace!("start game over close animation");
+
+        try_join!(
+            self.cx
+                .play_animation(self.text_title, "/animation/game-over/content-close.yml"),
+            self.cx
+                .play_animation(self.text_depth, "/animation/game-over/content-close.yml"),
+            self.cx
+                .play_animation(self.text_cause, "/animation/game-over/content-close.yml"),
+            self.cx
+                .play_animation(self.text_retry, "/animation/game-over/content-close.yml"),
+            self.cx.play_animation(
+                self.text_return_to_title,
+                "/animation/game-over/content-close.yml"
+            ),
+        )
+        .expect("animation not found");
+        self.cx.stop_bgm();
+
+        result
+    }
+}
+impl<'a> Drop for GameOverScene<'a> {
+    fn drop(&mut self) {
+        self.cx.delete_entity(self.cover);
+        self.cx.delete_entity(self.text_title);
+        self.cx.delete_entity(self.text_depth);
+        self.cx.delete_entity(self.text_cause);
+        self.cx.delete_entity(self.text_retry);
+        self.cx.delete_entity(self.text_return_to_title);
+    }
+}
+
+pub async fn game_over(cx: &AnimationEngineContext, summary: &GameOverSummary) -> GameOverResult {
+    info!("Enter GameOver Scene!");
+    GameOverScene::new(cx, summary).start().await
+}
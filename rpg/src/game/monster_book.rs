@@ -0,0 +1,343 @@
+use animation_engine::executor::*;
+use animation_engine::*;
+use futures::future::try_join_all;
+use futures::{select, try_join, FutureExt};
+use log::{info, trace};
+use std::time::Duration;
+
+use crate::game::game;
+use crate::game_data::*;
+use crate::input;
+use crate::localization;
+use crate::save_data;
+
+/// 1ページに表示する項目の数
+const PAGE_ROWS: usize = 8;
+
+struct MonsterBook<'a> {
+    cx: &'a AnimationEngineContext,
+    bg_cover: Entity,
+    bg: Entity,
+    monster_image: Entity,
+    text_title: Entity,
+    text_rows: Vec<Entity>,
+    text_page: Entity,
+    text_name: Entity,
+    text_description: Entity,
+    text_hp: Entity,
+    text_attack: Entity,
+    text_defense: Entity,
+    text_defeated: Entity,
+}
+impl<'a> MonsterBook<'a> {
+    fn new(cx: &'a AnimationEngineContext) -> Self {
+        let bg_cover = cx.add_rect(AddRectInfo {
+            width: 1280.0,
+            height: 720.0,
+            z: 400,
+            r: 0.0,
+            g: 0.0,
+            b: 0.0,
+            a: 0.4,
+            ..Default::default()
+        });
+        let bg = cx.add_image(AddImageInfo {
+            name: "/image/ui/menu-bg.png".into(),
+            x: -25.0,
+            y: 0.0,
+            z: 405,
+            ..Default::default()
+        });
+        let monster_image = cx.add_image(AddImageInfo {
+            x: 760.0,
+            y: 80.0,
+            z: 407,
+            scale: 0.6,
+            ..Default::default()
+        });
+        let text_title = cx.add_text(AddTextInfo {
+            key: "monster-book-title".into(),
+            font_size: 72.0,
+            x: 550.0,
+            y: 30.0,
+            z: 410,
+            ..Default::default()
+        });
+        let text_rows = (0..PAGE_ROWS)
+            .map(|i| {
+                cx.add_text(AddTextInfo {
+                    font_size: 30.0,
+                    x: 120.0,
+                    y: 150.0 + 50.0 * i as f32,
+                    z: 410,
+                    r: 212.0 / 255.0,
+                    g: 1.0,
+                    b: 1.0,
+                    ..Default::default()
+                })
+            })
+            .collect();
+        let text_page = cx.add_text(AddTextInfo {
+            key: "monster-book-page".into(),
+            format_args: vec!["1".into(), "1".into()],
+            font_size: 26.0,
+            x: 120.0,
+            y: 160.0 + 50.0 * PAGE_ROWS as f32,
+            z: 410,
+            ..Default::default()
+        });
+        let text_name = cx.add_text(AddTextInfo {
+            font_size: 36.0,
+            x: 480.0,
+            y: 150.0,
+            z: 410,
+            ..Default::default()
+        });
+        let text_description = cx.add_text(AddTextInfo {
+            font_size: 22.0,
+            x: 480.0,
+            y: 215.0,
+            z: 410,
+            ..Default::default()
+        });
+        let text_hp = cx.add_text(AddTextInfo {
+            key: "monster-book-hp".into(),
+            format_args: vec!["".into()],
+            font_size: 26.0,
+            x: 480.0,
+            y: 420.0,
+            z: 410,
+            ..Default::default()
+        });
+        let text_attack = cx.add_text(AddTextInfo {
+            key: "monster-book-attack".into(),
+            format_args: vec!["".into()],
+            font_size: 26.0,
+            x: 480.0,
+            y: 460.0,
+            z: 410,
+            ..Default::default()
+        });
+        let text_defense = cx.add_text(AddTextInfo {
+            key: "monster-book-defense".into(),
+            format_args: vec!["".into()],
+            font_size: 26.0,
+            x: 480.0,
+            y: 500.0,
+            z: 410,
+            ..Default::default()
+        });
+        let text_defeated = cx.add_text(AddTextInfo {
+            key: "monster-book-defeated".into(),
+            format_args: vec!["0".into()],
+            font_size: 26.0,
+            x: 480.0,
+            y: 560.0,
+            z: 410,
+            r: 212.0 / 255.0,
+            g: 1.0,
+            b: 1.0,
+            ..Default::default()
+        });
+        Self {
+            cx,
+            bg_cover,
+            bg,
+            monster_image,
+            text_title,
+            text_rows,
+            text_page,
+            text_name,
+            text_description,
+            text_hp,
+            text_attack,
+            text_defense,
+            text_defeated,
+        }
+    }
+
+    fn contents(&self) -> Vec<Entity> {
+        let mut contents = vec![
+            self.monster_image,
+            self.text_title,
+            self.text_page,
+            self.text_name,
+            self.text_description,
+            self.text_hp,
+            self.text_attack,
+            self.text_defense,
+            self.text_defeated,
+        ];
+        contents.extend(self.text_rows.iter());
+        contents
+    }
+
+    async fn enter_animation(&self) {
+        trace!("start monster book enter animation");
+
+        try_join!(
+            self.cx
+                .play_animation(self.bg_cover, "/animation/user-guide/cover-enter.yml"),
+            self.cx
+                .play_animation(self.bg, "/animation/user-guide/bg-enter.yml"),
+            try_join_all(self.contents().into_iter().map(|entity| {
+                self.cx
+                    .play_animation(entity, "/animation/user-guide/content-enter.yml")
+            })),
+        )
+        .expect("animation not found");
+
+        trace!("finish monster book enter animation");
+    }
+
+    async fn close_animation(&self) {
+        trace!("start monster book close animation");
+
+        try_join!(
+            self.cx
+                .play_animation(self.bg_cover, "/animation/user-guide/cover-close.yml"),
+            self.cx
+                .play_animation(self.bg, "/animation/user-guide/bg-close.yml"),
+            try_join_all(self.contents().into_iter().map(|entity| {
+                self.cx
+                    .play_animation(entity, "/animation/user-guide/content-close.yml")
+            })),
+        )
+        .expect("animation not found");
+
+        trace!("finish monster book close animation");
+    }
+
+    /// 出会っていない敵は名前と能力を伏せ、影の画像だけを表示する
+    fn update(&self, index: usize, enemy_data: &[EnemyData], save_data: &save_data::SaveData) {
+        let page = index / PAGE_ROWS;
+        let page_count = enemy_data.len().div_ceil(PAGE_ROWS);
+        for (row, &text) in self.text_rows.iter().enumerate() {
+            let i = page * PAGE_ROWS + row;
+            match enemy_data.get(i) {
+                Some(enemy) => {
+                    let name = if save_data.is_enemy_encountered(enemy.id) {
+                        localization::get_text(&enemy.enemy_name)
+                    } else {
+                        localization::get_text("monster-book-unknown-name")
+                    };
+                    self.cx.set_text_key(text, "monster-book-entry").unwrap();
+                    self.cx
+                        .set_text_format_args(text, &[&(i + 1).to_string(), &name])
+                        .unwrap();
+                    self.cx
+                        .set_opacity(text, if i == index { 1.0 } else { 0.4 })
+                        .unwrap();
+                }
+                None => self.cx.set_opacity(text, 0.0).unwrap(),
+            }
+        }
+        self.cx
+            .set_text_format_args(
+                self.text_page,
+                &[&(page + 1).to_string(), &page_count.max(1).to_string()],
+            )
+            .unwrap();
+
+        let enemy = match enemy_data.get(index) {
+            Some(enemy) => enemy,
+            None => return,
+        };
+        if save_data.is_enemy_encountered(enemy.id) {
+            self.cx
+                .set_image_name(self.monster_image, &enemy.image)
+                .unwrap();
+            self.cx
+                .set_text_key(self.text_name, &enemy.enemy_name)
+                .unwrap();
+            self.cx
+                .set_text_key(self.text_description, &enemy.description)
+                .unwrap();
+            self.cx
+                .set_text_format_args(self.text_hp, &[&enemy.max_hp.to_string()])
+                .unwrap();
+            self.cx
+                .set_text_format_args(self.text_attack, &[&enemy.attack.to_string()])
+                .unwrap();
+            self.cx
+                .set_text_format_args(self.text_defense, &[&enemy.defense.to_string()])
+                .unwrap();
+        } else {
+            self.cx
+                .set_image_name(self.monster_image, &enemy.shadow_image)
+                .unwrap();
+            self.cx
+                .set_text_key(self.text_name, "monster-book-unknown-name")
+                .unwrap();
+            self.cx
+                .set_text_key(self.text_description, "monster-book-unknown-description")
+                .unwrap();
+            let unknown = localization::get_text("monster-book-unknown-value");
+            for text in [self.text_hp, self.text_attack, self.text_defense] {
+                self.cx.set_text_format_args(text, &[&unknown]).unwrap();
+            }
+        }
+        self.cx
+            .set_text_format_args(
+                self.text_defeated,
+                &[&save_data.defeated_count(enemy.id).to_string()],
+            )
+            .unwrap();
+    }
+
+    async fn start(&self, enemy_data: &[EnemyData], save_data: &save_data::SaveData) {
+        self.cx.play_sfx("/audio/sfx/menu.ogg");
+        let len = enemy_data.len().max(1);
+        let mut index = 0;
+        self.update(index, enemy_data, save_data);
+        self.enter_animation().await;
+
+        loop {
+            self.update(index, enemy_data, save_data);
+            select! {
+                _ = input::wait_up(self.cx).fuse() => {
+                    index = (index + len - 1) % len;
+                    self.cx.play_sfx("/audio/sfx/cursor.ogg");
+                    delay(Duration::from_millis(150)).await;
+                }
+                _ = input::wait_down(self.cx).fuse() => {
+                    index = (index + 1) % len;
+                    self.cx.play_sfx("/audio/sfx/cursor.ogg");
+                    delay(Duration::from_millis(150)).await;
+                }
+                // 左右でページ単位に移動する
+                _ = input::wait_left(self.cx).fuse() => {
+                    index = index.saturating_sub(PAGE_ROWS);
+                    self.cx.play_sfx("/audio/sfx/cursor.ogg");
+                    delay(Duration::from_millis(150)).await;
+                }
+                _ = input::wait_right(self.cx).fuse() => {
+                    index = (index + PAGE_ROWS).min(len - 1);
+                    self.cx.play_sfx("/audio/sfx/cursor.ogg");
+                    delay(Duration::from_millis(150)).await;
+                }
+                _ = input::wait_cancel_button(self.cx).fuse() => break,
+            }
+            next_frame().await;
+        }
+
+        self.cx.play_sfx("/audio/sfx/cancel.ogg");
+        self.close_animation().await;
+    }
+}
+impl<'a> Drop for MonsterBook<'a> {
+    fn drop(&mut self) {
+        self.cx.delete_entity(self.bg_cover);
+        self.cx.delete_entity(self.bg);
+        for entity in self.contents() {
+            self.cx.delete_entity(entity);
+        }
+    }
+}
+
+pub async fn monster_book(cx: &AnimationEngineContext, global_data: &game::GlobalData) {
+    info!("Enter MonsterBook Scene!");
+    MonsterBook::new(cx)
+        .start(global_data.game_data.enemy_data(), &global_data.save_data)
+        .await;
+}
@@ -314,7 +314,7 @@ impl<'a> OpeningScene<'a> {
     }
 
     #[async_recursion(?Send)]
-    async fn prologue(&self, opening_data: &OpeningData, rng: &mut ThreadRng) {
+    async fn prologue(&self, opening_data: &OpeningData, rng: &mut StdRng) {
         match opening_data {
             OpeningData::Data { data } => {
                 self.cx
@@ -371,7 +371,7 @@ impl<'a> OpeningScene<'a> {
         }
     }
 
-    async fn prologue_index(&self, index: usize, opening_data: &OpeningData, rng: &mut ThreadRng) {
+    async fn prologue_index(&self, index: usize, opening_data: &OpeningData, rng: &mut StdRng) {
         trace!("start opening prologue: {}", index);
 
         match opening_data {
@@ -1,36 +1,137 @@
+use rand::distributions::*;
+use rand::prelude::*;
 use std::collections::{HashMap, HashSet};
 
 use crate::game_data::*;
 
+#[derive(Clone)]
 pub struct PlayerState {
+    hp: i32,
+    max_hp: i32,
+    tp: i32,
+    max_tp: i32,
+    gold: u32,
     owned_item: HashMap<ItemId, u32>,
     owned_skill: HashSet<SkillId>,
+    flags: HashSet<String>,
+    counters: HashMap<String, i32>,
+    /// セーブデータにも保存されるフラグとカウンタの名前
+    persistent_flags: HashSet<String>,
+    persistent_counters: HashSet<String>,
+    battle_count: u32,
+    turn_count: u32,
 }
 impl PlayerState {
-    pub fn new() -> Self {
+    pub fn new(player_data: &PlayerData) -> Self {
         Self {
+            hp: player_data.max_hp,
+            max_hp: player_data.max_hp,
+            tp: player_data.max_tp,
+            max_tp: player_data.max_tp,
+            gold: 0,
             owned_item: HashMap::new(),
             owned_skill: HashSet::new(),
+            flags: HashSet::new(),
+            counters: HashMap::new(),
+            persistent_flags: HashSet::new(),
+            persistent_counters: HashSet::new(),
+            battle_count: 0,
+            turn_count: 0,
         }
     }
 
+    pub fn get_hp(&self) -> (i32, i32) {
+        (self.hp, self.max_hp)
+    }
+
+    pub fn get_tp(&self) -> (i32, i32) {
+        (self.tp, self.max_tp)
+    }
+
+    pub fn set_hp(&mut self, hp: i32) {
+        self.hp = hp.clamp(0, self.max_hp);
+    }
+
+    pub fn set_tp(&mut self, tp: i32) {
+        self.tp = tp.clamp(0, self.max_tp);
+    }
+
+    pub fn heal(&mut self, amount: i32) {
+        self.set_hp(self.hp + amount);
+    }
+
+    pub fn damage(&mut self, amount: i32) {
+        self.set_hp(self.hp - amount);
+    }
+
+    pub fn restore_tp(&mut self, amount: i32) {
+        self.set_tp(self.tp + amount);
+    }
+
+    pub fn get_gold(&self) -> u32 {
+        self.gold
+    }
+
+    pub fn add_gold(&mut self, amount: u32) {
+        self.gold = self.gold.saturating_add(amount);
+    }
+
     pub fn add_item(&mut self, item_id: ItemId) {
         *self.owned_item.entry(item_id).or_default() += 1;
     }
 
+    /// 持っていない場合は状態を変えずにエラーを返す
     pub fn use_item(&mut self, item_id: ItemId) -> anyhow::Result<()> {
-        let items = self.owned_item.entry(item_id).or_default();
-        if *items == 0 {
+        if self.item_count(item_id) == 0 {
             Err(anyhow::Error::msg(format!(
                 "Item not owned. item id: {:?}",
                 item_id
             )))
         } else {
-            *items -= 1;
+            self.remove_item(item_id, 1);
             Ok(())
         }
     }
 
+    /// 持っている分だけ取り除き、取り除いた数を返す
+    pub fn remove_item(&mut self, item_id: ItemId, count: u32) -> u32 {
+        let owned = self.item_count(item_id);
+        let removed = owned.min(count);
+        if owned == removed {
+            self.owned_item.remove(&item_id);
+        } else {
+            self.owned_item.insert(item_id, owned - removed);
+        }
+        removed
+    }
+
+    pub fn can_pay(&self, costs: &[Cost]) -> bool {
+        costs.iter().all(|cost| match cost {
+            Cost::Item { item_id, count } => self.item_count(*item_id) >= *count,
+            Cost::Gold { amount } => self.gold >= *amount,
+        })
+    }
+
+    /// 一つでも足りない場合は何も支払わずにエラーを返す
+    pub fn pay(&mut self, costs: &[Cost]) -> anyhow::Result<()> {
+        if !self.can_pay(costs) {
+            return Err(anyhow::Error::msg("Not enough items or gold to pay"));
+        }
+        for cost in costs.iter() {
+            match cost {
+                Cost::Item { item_id, count } => {
+                    self.remove_item(*item_id, *count);
+                }
+                Cost::Gold { amount } => self.gold -= amount,
+            }
+        }
+        Ok(())
+    }
+
+    pub fn item_count(&self, item_id: ItemId) -> u32 {
+        self.owned_item.get(&item_id).copied().unwrap_or(0)
+    }
+
     pub fn get_items(&self) -> Vec<(ItemId, u32)> {
         let mut v = self
             .owned_item
@@ -72,9 +173,180 @@ impl PlayerState {
         self.owned_skill.insert(skill_id);
     }
 
+    pub fn has_skill(&self, skill_id: SkillId) -> bool {
+        self.owned_skill.contains(&skill_id)
+    }
+
+    pub fn has_skill_type(&self, skill_type: usize, skill_list: &[SkillData]) -> bool {
+        skill_list
+            .iter()
+            .any(|s| s.skill_type == skill_type && self.owned_skill.contains(&s.id))
+    }
+
+    pub fn is_flag_set(&self, flag: &str) -> bool {
+        self.flags.contains(flag)
+    }
+
+    pub fn set_flag(&mut self, flag: &str) {
+        self.flags.insert(flag.to_string());
+    }
+
+    pub fn clear_flag(&mut self, flag: &str) {
+        self.flags.remove(flag);
+    }
+
+    /// 一度も加算されていないカウンタは0として扱う
+    pub fn counter(&self, counter: &str) -> i32 {
+        self.counters.get(counter).copied().unwrap_or(0)
+    }
+
+    pub fn add_counter(&mut self, counter: &str, amount: i32) {
+        *self.counters.entry(counter.to_string()).or_default() += amount;
+    }
+
+    pub fn record_battle(&mut self, turns: u32) {
+        self.battle_count += 1;
+        self.turn_count += turns;
+    }
+
+    /// 戦闘回数と合計ターン数
+    pub fn get_battle_stats(&self) -> (u32, u32) {
+        (self.battle_count, self.turn_count)
+    }
+
+    pub fn restore_battle_stats(&mut self, battle_count: u32, turn_count: u32) {
+        self.battle_count = battle_count;
+        self.turn_count = turn_count;
+    }
+
+    pub fn get_flags(&self) -> Vec<String> {
+        let mut v = self.flags.iter().cloned().collect::<Vec<_>>();
+        v.sort();
+        v
+    }
+
+    pub fn get_counters(&self) -> Vec<(String, i32)> {
+        let mut v = self
+            .counters
+            .iter()
+            .map(|(counter, &value)| (counter.clone(), value))
+            .collect::<Vec<_>>();
+        v.sort();
+        v
+    }
+
+    pub fn mark_persistent_flag(&mut self, flag: &str) {
+        self.persistent_flags.insert(flag.to_string());
+    }
+
+    pub fn mark_persistent_counter(&mut self, counter: &str) {
+        self.persistent_counters.insert(counter.to_string());
+    }
+
+    pub fn get_persistent_keys(&self) -> (Vec<String>, Vec<String>) {
+        let mut flags = self.persistent_flags.iter().cloned().collect::<Vec<_>>();
+        flags.sort();
+        let mut counters = self.persistent_counters.iter().cloned().collect::<Vec<_>>();
+        counters.sort();
+        (flags, counters)
+    }
+
+    /// 永続するフラグとカウンタをセーブデータに保存された内容で上書きする。
+    /// セーブデータにないものは消す
+    pub fn restore_persistent<'b>(
+        &mut self,
+        flags: impl Iterator<Item = &'b String>,
+        counters: impl Iterator<Item = (&'b String, &'b i32)>,
+    ) {
+        let persistent_flags = &self.persistent_flags;
+        self.flags.retain(|flag| !persistent_flags.contains(flag));
+        let persistent_counters = &self.persistent_counters;
+        self.counters
+            .retain(|counter, _| !persistent_counters.contains(counter));
+        for flag in flags {
+            self.flags.insert(flag.clone());
+            self.persistent_flags.insert(flag.clone());
+        }
+        for (counter, &value) in counters {
+            self.counters.insert(counter.clone(), value);
+            self.persistent_counters.insert(counter.clone());
+        }
+    }
+
     pub fn get_skills(&self) -> Vec<SkillId> {
         let mut v = self.owned_skill.iter().map(|&id| id).collect::<Vec<_>>();
         v.sort_by_key(|s| s.0);
         v
     }
+
+    /// 未習得のスキルからレアリティごとの重みでcount個まで候補を選ぶ
+    pub fn pick_skill_candidates(
+        &self,
+        skills: &[SkillRarityWeight],
+        count: u8,
+        skill_list: &[SkillData],
+        rng: &mut impl Rng,
+    ) -> Vec<SkillId> {
+        let owned_skills = self.get_skills();
+        let mut list = skills
+            .iter()
+            .flat_map(|sr| {
+                let rarity = sr.rarity;
+                let weight = sr.weight;
+                skill_list
+                    .iter()
+                    .filter(move |s| s.rarity == rarity)
+                    .map(move |s| (s.id, s.rarity_weight * weight))
+            })
+            .filter(|(s, w)| !owned_skills.contains(s) && *w > 0.0)
+            .collect::<Vec<_>>();
+        let mut candidate_skills = vec![];
+        for _ in 0..(list.len().min(count as usize)) {
+            let dist = WeightedIndex::new(list.iter().map(|(_, w)| w)).unwrap();
+            let index = dist.sample(rng);
+            let (skill_id, _) = list.remove(index);
+            candidate_skills.push(skill_id);
+        }
+        candidate_skills.sort_by_key(|s| s.0);
+        candidate_skills
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_player_state() -> PlayerState {
+        let game_data =
+            GameData::load_from_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/resources")).unwrap();
+        PlayerState::new(&game_data.player_data()[0])
+    }
+
+    #[test]
+    fn it_should_remove_persistent_flag_cleared_after_checkpoint() {
+        let mut player_state = new_player_state();
+        let mut saved_flags = HashSet::new();
+        let mut saved_counters = HashMap::new();
+
+        player_state.set_flag("persistent");
+        player_state.mark_persistent_flag("persistent");
+        saved_flags.insert("persistent".to_owned());
+        player_state.add_counter("count", 2);
+        player_state.mark_persistent_counter("count");
+        saved_counters.insert("count".to_owned(), 2);
+        player_state.set_flag("run");
+        let mut checkpoint = player_state.clone();
+
+        player_state.clear_flag("persistent");
+        player_state.mark_persistent_flag("persistent");
+        saved_flags.remove("persistent");
+        player_state.add_counter("count", 3);
+        player_state.mark_persistent_counter("count");
+        *saved_counters.get_mut("count").unwrap() += 3;
+
+        checkpoint.restore_persistent(saved_flags.iter(), saved_counters.iter());
+        assert!(!checkpoint.is_flag_set("persistent"));
+        assert!(checkpoint.is_flag_set("run"));
+        assert_eq!(checkpoint.counter("count"), 5);
+    }
 }
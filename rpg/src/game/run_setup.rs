@@ -0,0 +1,416 @@
+use animation_engine::executor::*;
+use animation_engine::*;
+use futures::future::try_join_all;
+use futures::{select, try_join, FutureExt};
+use log::{info, trace};
+use rand::prelude::*;
+use std::time::Duration;
+
+use crate::input;
+
+/// 手で入力できるようにシードは10桁までにする
+const SEED_DIGITS: usize = 10;
+const SEED_DIGIT_WIDTH: f32 = 32.0;
+const SLANT: f32 = 0.17632698 * 130.0;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum SeedMode {
+    Random,
+    Daily,
+    Custom,
+}
+impl SeedMode {
+    fn text_key(self) -> &'static str {
+        match self {
+            SeedMode::Random => "run-setup-mode-random",
+            SeedMode::Daily => "run-setup-mode-daily",
+            SeedMode::Custom => "run-setup-mode-custom",
+        }
+    }
+
+    fn prev(self) -> Self {
+        match self {
+            SeedMode::Random => SeedMode::Custom,
+            SeedMode::Daily => SeedMode::Random,
+            SeedMode::Custom => SeedMode::Daily,
+        }
+    }
+
+    fn next(self) -> Self {
+        match self {
+            SeedMode::Random => SeedMode::Daily,
+            SeedMode::Daily => SeedMode::Custom,
+            SeedMode::Custom => SeedMode::Random,
+        }
+    }
+}
+
+fn random_seed() -> u64 {
+    rand::thread_rng().gen_range(0..10u64.pow(SEED_DIGITS as u32))
+}
+
+/// 日付をそのまま数字にするので、同じ日なら誰が遊んでも同じシードになる
+fn daily_seed() -> u64 {
+    chrono::Local::now()
+        .format("%Y%m%d")
+        .to_string()
+        .parse()
+        .expect("Failed to make daily seed")
+}
+
+/// 自分で入力する場合は今のシードをそのまま使う
+fn mode_seed(mode: SeedMode, current: u64) -> u64 {
+    match mode {
+        SeedMode::Random => random_seed(),
+        SeedMode::Daily => daily_seed(),
+        SeedMode::Custom => current,
+    }
+}
+
+fn seed_digits(seed: u64) -> Vec<u8> {
+    (0..SEED_DIGITS)
+        .rev()
+        .map(|i| (seed / 10u64.pow(i as u32) % 10) as u8)
+        .collect()
+}
+
+fn seed_from_digits(digits: &[u8]) -> u64 {
+    digits.iter().fold(0, |seed, &d| seed * 10 + d as u64)
+}
+
+struct RunSetup<'a> {
+    cx: &'a AnimationEngineContext,
+    bg_cover: Entity,
+    bg: Entity,
+    cursor: Entity,
+    arrow_mode: Entity,
+    text_title: Entity,
+    text_mode_title: Entity,
+    text_mode_value: Entity,
+    text_seed_title: Entity,
+    text_seed_digits: Vec<Entity>,
+    text_start: Entity,
+}
+impl<'a> RunSetup<'a> {
+    fn new(cx: &'a AnimationEngineContext) -> Self {
+        let bg_cover = cx.add_rect(AddRectInfo {
+            width: 1280.0,
+            height: 720.0,
+            z: 700,
+            r: 0.0,
+            g: 0.0,
+            b: 0.0,
+            a: 0.4,
+            ..Default::default()
+        });
+        let bg = cx.add_image(AddImageInfo {
+            name: "/image/ui/menu-bg.png".into(),
+            x: -25.0,
+            y: 0.0,
+            z: 705,
+            ..Default::default()
+        });
+        let cursor = cx.add_image(AddImageInfo {
+            name: "/image/ui/options-part-1.png".into(),
+            x: 200.0,
+            y: 150.0,
+            z: 705,
+            ..Default::default()
+        });
+        let arrow_mode = cx.add_image(AddImageInfo {
+            name: "/image/ui/options-part-2.png".into(),
+            x: 550.0,
+            y: 170.0,
+            z: 710,
+            ..Default::default()
+        });
+        let text_title = cx.add_text(AddTextInfo {
+            key: "run-setup-title".into(),
+            font_size: 72.0,
+            x: 550.0,
+            y: 30.0,
+            z: 710,
+            ..Default::default()
+        });
+        let text_mode_title = cx.add_text(AddTextInfo {
+            key: "run-setup-mode".into(),
+            font_size: 56.0,
+            x: 250.0,
+            y: 165.0,
+            z: 710,
+            ..Default::default()
+        });
+        let text_mode_value = cx.add_text(AddTextInfo {
+            key: SeedMode::Random.text_key().into(),
+            font_size: 36.0,
+            x: 605.0,
+            y: 175.0,
+            z: 710,
+            ..Default::default()
+        });
+        let text_seed_title = cx.add_text(AddTextInfo {
+            key: "run-setup-seed".into(),
+            font_size: 56.0,
+            x: 250.0 - SLANT,
+            y: 295.0,
+            z: 710,
+            ..Default::default()
+        });
+        let text_seed_digits = (0..SEED_DIGITS)
+            .map(|i| {
+                cx.add_text(AddTextInfo {
+                    key: "run-setup-seed-digit".into(),
+                    format_args: vec!["0".into()],
+                    font_size: 56.0,
+                    x: 550.0 - SLANT + SEED_DIGIT_WIDTH * i as f32,
+                    y: 295.0,
+                    z: 710,
+                    ..Default::default()
+                })
+            })
+            .collect();
+        let text_start = cx.add_text(AddTextInfo {
+            key: "run-setup-start".into(),
+            font_size: 56.0,
+            x: 500.0,
+            y: 555.0,
+            z: 710,
+            ..Default::default()
+        });
+        Self {
+            cx,
+            bg_cover,
+            bg,
+            cursor,
+            arrow_mode,
+            text_title,
+            text_mode_title,
+            text_mode_value,
+            text_seed_title,
+            text_seed_digits,
+            text_start,
+        }
+    }
+
+    fn contents(&self) -> Vec<Entity> {
+        let mut contents = vec![
+            self.cursor,
+            self.arrow_mode,
+            self.text_title,
+            self.text_mode_title,
+            self.text_mode_value,
+            self.text_seed_title,
+            self.text_start,
+        ];
+        contents.extend(self.text_seed_digits.iter());
+        contents
+    }
+
+    async fn enter_animation(&self) {
+        trace!("Start RunSetup enter animation");
+
+        try_join!(
+            self.cx
+                .play_animation(self.bg_cover, "/animation/options/cover-enter.yml"),
+            self.cx
+                .play_animation(self.bg, "/animation/options/bg-enter.yml"),
+            try_join_all(self.contents().into_iter().map(|entity| {
+                self.cx
+                    .play_animation(entity, "/animation/options/content-enter.yml")
+            })),
+        )
+        .expect("animation not found");
+
+        trace!("Finish RunSetup enter animation");
+    }
+
+    async fn close_animation(&self) {
+        trace!("Start RunSetup close animation");
+
+        try_join!(
+            self.cx
+                .play_animation(self.bg_cover, "/animation/options/cover-close.yml"),
+            self.cx
+                .play_animation(self.bg, "/animation/options/bg-close.yml"),
+            try_join_all(self.contents().into_iter().map(|entity| {
+                self.cx
+                    .play_animation(entity, "/animation/options/content-close.yml")
+            })),
+        )
+        .expect("animation not found");
+
+        trace!("Finish RunSetup close animation");
+    }
+
+    fn update_mode(&self, mode: SeedMode) {
+        self.cx
+            .set_text_key(self.text_mode_value, mode.text_key())
+            .unwrap();
+    }
+
+    /// editingが指定されている場合は、編集中の桁以外を薄く表示する
+    fn update_seed(&self, digits: &[u8], editing: Option<usize>) {
+        for (i, (&entity, digit)) in self.text_seed_digits.iter().zip(digits).enumerate() {
+            self.cx
+                .set_text_format_args(entity, &[&digit.to_string()])
+                .unwrap();
+            let opacity = match editing {
+                Some(index) if index != i => 0.4,
+                _ => 1.0,
+            };
+            self.cx.set_opacity(entity, opacity).unwrap();
+        }
+    }
+
+    /// 決定かキャンセルで編集を終える。編集した場合はtrueを返す
+    async fn edit_seed(&self, digits: &mut [u8]) -> bool {
+        let mut edited = false;
+        let mut index = 0;
+        loop {
+            self.update_seed(digits, Some(index));
+            select! {
+                _ = input::wait_left(self.cx).fuse() => {
+                    index = (index + SEED_DIGITS - 1) % SEED_DIGITS;
+                    self.cx.play_sfx("/audio/sfx/cursor.ogg");
+                    delay(Duration::from_millis(150)).await;
+                }
+                _ = input::wait_right(self.cx).fuse() => {
+                    index = (index + 1) % SEED_DIGITS;
+                    self.cx.play_sfx("/audio/sfx/cursor.ogg");
+                    delay(Duration::from_millis(150)).await;
+                }
+                _ = input::wait_up(self.cx).fuse() => {
+                    digits[index] = (digits[index] + 1) % 10;
+                    edited = true;
+                    self.cx.play_sfx("/audio/sfx/cursor.ogg");
+                    delay(Duration::from_millis(150)).await;
+                }
+                _ = input::wait_down(self.cx).fuse() => {
+                    digits[index] = (digits[index] + 9) % 10;
+                    edited = true;
+                    self.cx.play_sfx("/audio/sfx/cursor.ogg");
+                    delay(Duration::from_millis(150)).await;
+                }
+                _ = input::wait_select_button(self.cx).fuse() => break,
+                _ = input::wait_cancel_button(self.cx).fuse() => break,
+            }
+            next_frame().await;
+        }
+        self.cx.play_sfx("/audio/sfx/select.ogg");
+        self.update_seed(digits, None);
+        delay(Duration::from_millis(150)).await;
+        edited
+    }
+
+    async fn start(&self) -> Option<u64> {
+        self.cx.play_sfx("/audio/sfx/menu.ogg");
+        let mut mode = SeedMode::Random;
+        let mut digits = seed_digits(random_seed());
+        self.update_mode(mode);
+        self.update_seed(&digits, None);
+        self.enter_animation().await;
+
+        let mut index = 0;
+        let result = loop {
+            match index {
+                0 => {
+                    self.cx
+                        .set_position(self.cursor, 200.0, 150.0, 705)
+                        .unwrap();
+                }
+                1 => {
+                    self.cx
+                        .set_position(self.cursor, 200.0 - SLANT, 280.0, 705)
+                        .unwrap();
+                }
+                2 => {
+                    self.cx
+                        .set_position(self.cursor, 200.0 - SLANT * 3.0, 540.0, 705)
+                        .unwrap();
+                }
+                _ => unreachable!(),
+            }
+            select! {
+                _ = input::wait_up(self.cx).fuse() => {
+                    index = (index + 3 - 1) % 3;
+                    self.cx.play_sfx("/audio/sfx/cursor.ogg");
+                    delay(Duration::from_millis(150)).await;
+                }
+                _ = input::wait_down(self.cx).fuse() => {
+                    index = (index + 1) % 3;
+                    self.cx.play_sfx("/audio/sfx/cursor.ogg");
+                    delay(Duration::from_millis(150)).await;
+                }
+                _ = input::wait_left(self.cx).fuse() => {
+                    if index == 0 {
+                        self.cx.play_sfx("/audio/sfx/cursor.ogg");
+                        mode = mode.prev();
+                        digits = seed_digits(mode_seed(mode, seed_from_digits(&digits)));
+                    }
+                    delay(Duration::from_millis(150)).await;
+                }
+                _ = input::wait_right(self.cx).fuse() => {
+                    if index == 0 {
+                        self.cx.play_sfx("/audio/sfx/cursor.ogg");
+                        mode = mode.next();
+                        digits = seed_digits(mode_seed(mode, seed_from_digits(&digits)));
+                    }
+                    delay(Duration::from_millis(150)).await;
+                }
+                _ = input::wait_select_button(self.cx).fuse() => {
+                    match index {
+                        0 => (),
+                        1 => {
+                            self.cx.play_sfx("/audio/sfx/select.ogg");
+                            // 数字を変えたら自分で入力したシードとして扱う
+                            if self.edit_seed(&mut digits).await {
+                                mode = SeedMode::Custom;
+                            }
+                        }
+                        2 => {
+                            self.cx.play_sfx("/audio/sfx/select.ogg");
+                            break Some(seed_from_digits(&digits));
+                        }
+                        _ => unreachable!(),
+                    }
+                }
+                _ = input::wait_cancel_button(self.cx).fuse() => {
+                    self.cx.play_sfx("/audio/sfx/cancel.ogg");
+                    break None;
+                }
+            }
+            self.update_mode(mode);
+            self.update_seed(&digits, None);
+            next_frame().await;
+        };
+
+        self.close_animation().await;
+        result
+    }
+}
+impl<'a> Drop for RunSetup<'a> {
+    fn drop(&mut self) {
+        self.cx.delete_entity(self.bg);
+        self.cx.delete_entity(self.bg_cover);
+        self.cx.delete_entity(self.cursor);
+        self.cx.delete_entity(self.arrow_mode);
+        self.cx.delete_entity(self.text_title);
+        self.cx.delete_entity(self.text_mode_title);
+        self.cx.delete_entity(self.text_mode_value);
+        self.cx.delete_entity(self.text_seed_title);
+        for &entity in self.text_seed_digits.iter() {
+            self.cx.delete_entity(entity);
+        }
+        self.cx.delete_entity(self.text_start);
+    }
+}
+
+/// 探索に使うシードを決める。キャンセルした場合はNone
+pub async fn run_setup(cx: &AnimationEngineContext) -> Option<u64> {
+    info!("Enter RunSetup");
+    let seed = RunSetup::new(cx).start().await;
+    if let Some(seed) = seed {
+        info!("Run seed: {}", seed);
+    }
+    seed
+}
@@ -0,0 +1,214 @@
+use animation_engine::*;
+use chrono::{Local, TimeZone};
+use futures::future::try_join_all;
+use futures::{select, try_join, FutureExt};
+use log::{info, trace};
+
+use crate::game::game;
+use crate::input;
+use crate::localization;
+use crate::save_data::*;
+
+/// 直近の記録を何件表示するか
+const RECENT_RUNS: usize = 6;
+
+struct Statistics<'a> {
+    cx: &'a AnimationEngineContext,
+    bg_cover: Entity,
+    bg: Entity,
+    texts: Vec<Entity>,
+}
+impl<'a> Statistics<'a> {
+    fn new(cx: &'a AnimationEngineContext, global_data: &game::GlobalData) -> Self {
+        let bg_cover = cx.add_rect(AddRectInfo {
+            width: 1280.0,
+            height: 720.0,
+            z: 400,
+            r: 0.0,
+            g: 0.0,
+            b: 0.0,
+            a: 0.4,
+            ..Default::default()
+        });
+        let bg = cx.add_image(AddImageInfo {
+            name: "/image/ui/menu-bg.png".into(),
+            x: -25.0,
+            y: 0.0,
+            z: 405,
+            ..Default::default()
+        });
+
+        let history = global_data.save_data.run_history();
+        let totals = global_data.save_data.run_totals();
+        let player_data = global_data.game_data.player_data();
+        let max_depth = global_data.game_data.max_depth();
+
+        let mut texts = vec![cx.add_text(AddTextInfo {
+            key: "statistics-title".into(),
+            font_size: 72.0,
+            x: 550.0,
+            y: 30.0,
+            z: 410,
+            ..Default::default()
+        })];
+        // headingは見出しとして大きめの水色で表示する
+        let mut add_text = |key: &str, format_args: Vec<String>, x: f32, y: f32, heading: bool| {
+            let (font_size, r) = if heading {
+                (30.0, 212.0 / 255.0)
+            } else {
+                (22.0, 1.0)
+            };
+            texts.push(cx.add_text(AddTextInfo {
+                key: key.into(),
+                format_args,
+                font_size,
+                x,
+                y,
+                z: 410,
+                r,
+                g: 1.0,
+                b: 1.0,
+                ..Default::default()
+            }));
+        };
+
+        add_text(
+            "statistics-totals",
+            vec![
+                totals.runs.to_string(),
+                totals.clears.to_string(),
+                totals.battles.to_string(),
+                totals.turns.to_string(),
+            ],
+            120.0,
+            140.0,
+            true,
+        );
+
+        add_text("statistics-best-depth", vec![], 120.0, 200.0, true);
+        for (i, player) in player_data.iter().enumerate() {
+            let y = 245.0 + 36.0 * i as f32;
+            add_text(&player.opening_legendary_name, vec![], 160.0, y, false);
+            match totals.best_depth.get(&player.index) {
+                Some(depth) => add_text(
+                    "statistics-depth",
+                    vec![depth.to_string(), max_depth.to_string()],
+                    500.0,
+                    y,
+                    false,
+                ),
+                None => add_text("statistics-no-depth", vec![], 500.0, y, false),
+            }
+        }
+
+        let y = 245.0 + 36.0 * player_data.len() as f32 + 30.0;
+        add_text("statistics-recent-runs", vec![], 120.0, y, true);
+        if history.is_empty() {
+            add_text("statistics-no-runs", vec![], 160.0, y + 45.0, false);
+        }
+        for (i, record) in history.iter().rev().take(RECENT_RUNS).enumerate() {
+            let y = y + 45.0 + 36.0 * i as f32;
+            let date = Local
+                .timestamp_opt(record.timestamp, 0)
+                .single()
+                .map(|t| t.format("%Y/%m/%d %H:%M").to_string())
+                .unwrap_or_default();
+            add_text("statistics-date", vec![date], 120.0, y, false);
+            match player_data.iter().find(|p| p.index == record.player_index) {
+                Some(player) => add_text(&player.opening_legendary_name, vec![], 320.0, y, false),
+                None => add_text("statistics-unknown-player", vec![], 320.0, y, false),
+            }
+            add_text(
+                "statistics-depth",
+                vec![record.depth.to_string(), record.max_depth.to_string()],
+                620.0,
+                y,
+                false,
+            );
+            add_text(
+                "statistics-battles",
+                vec![
+                    record.battle_count.to_string(),
+                    record.turn_count.to_string(),
+                ],
+                760.0,
+                y,
+                false,
+            );
+            match &record.result {
+                RunResult::Clear => add_text("statistics-result-clear", vec![], 950.0, y, false),
+                RunResult::KilledBy { enemy_name } => add_text(
+                    "game-over-killed-by",
+                    vec![localization::get_text(enemy_name)],
+                    950.0,
+                    y,
+                    false,
+                ),
+                RunResult::Exhausted => add_text("game-over-exhausted", vec![], 950.0, y, false),
+            }
+        }
+
+        Self {
+            cx,
+            bg_cover,
+            bg,
+            texts,
+        }
+    }
+
+    async fn start(&self) {
+        self.cx.play_sfx("/audio/sfx/menu.ogg");
+
+        trace!("start statistics enter animation");
+
+        try_join!(
+            self.cx
+                .play_animation(self.bg_cover, "/animation/user-guide/cover-enter.yml"),
+            self.cx
+                .play_animation(self.bg, "/animation/user-guide/bg-enter.yml"),
+            try_join_all(self.texts.iter().map(|&text| {
+                self.cx
+                    .play_animation(text, "/animation/user-guide/content-enter.yml")
+            })),
+        )
+        .expect("animation not found");
+
+        trace!("finish statistics enter animation");
+
+        select! {
+            _ = input::wait_select_button(self.cx).fuse() => (),
+            _ = input::wait_cancel_button(self.cx).fuse() => (),
+        }
+        self.cx.play_sfx("/audio/sfx/cancel.ogg");
+
+        trace!("start statistics close animation");
+
+        try_join!(
+            self.cx
+                .play_animation(self.bg_cover, "/animation/user-guide/cover-close.yml"),
+            self.cx
+                .play_animation(self.bg, "/animation/user-guide/bg-close.yml"),
+            try_join_all(self.texts.iter().map(|&text| {
+                self.cx
+                    .play_animation(text, "/animation/user-guide/content-close.yml")
+            })),
+        )
+        .expect("animation not found");
+
+        trace!("finish statistics close animation");
+    }
+}
+impl<'a> Drop for Statistics<'a> {
+    fn drop(&mut self) {
+        self.cx.delete_entity(self.bg_cover);
+        self.cx.delete_entity(self.bg);
+        for &text in self.texts.iter() {
+            self.cx.delete_entity(text);
+        }
+    }
+}
+
+pub async fn statistics(cx: &AnimationEngineContext, global_data: &game::GlobalData) {
+    info!("Enter Statistics Scene!");
+    Statistics::new(cx, global_data).start().await;
+}
@@ -1,19 +1,58 @@
 use animation_engine::executor::*;
 use animation_engine::*;
+use futures::future::try_join_all;
 use futures::{select, try_join, FutureExt};
 use log::{info, trace};
 use std::time::Duration;
 
 use crate::game::game;
+use crate::game::monster_book;
 use crate::game::options;
+use crate::game::run_setup;
+use crate::game::statistics;
 use crate::game::user_guide;
 use crate::input;
 
 pub enum TitleResult {
-    StartGame,
+    Continue,
+    /// 探索に使うシード
+    StartGame(u64),
     Exit,
 }
 
+/// 一番下の項目の位置。上の項目ほど右にずれる
+const MENU_X: f32 = 160.0;
+const MENU_Y: f32 = 575.0;
+const MENU_LINE_HEIGHT: f32 = 50.0;
+const MENU_SLANT: f32 = 8.816349;
+/// 項目の位置からカーソルの位置までのずれ
+const CURSOR_OFFSET_X: f32 = -22.265396;
+const CURSOR_OFFSET_Y: f32 = -5.0;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum TitleMenu {
+    Continue,
+    GameStart,
+    MonsterBook,
+    Statistics,
+    UserGuide,
+    Options,
+    Exit,
+}
+impl TitleMenu {
+    fn text_key(self) -> &'static str {
+        match self {
+            TitleMenu::Continue => "continue",
+            TitleMenu::GameStart => "game-start",
+            TitleMenu::MonsterBook => "monster-book",
+            TitleMenu::Statistics => "statistics",
+            TitleMenu::UserGuide => "user-guide",
+            TitleMenu::Options => "options",
+            TitleMenu::Exit => "exit",
+        }
+    }
+}
+
 struct TitleScene<'a> {
     cx: &'a AnimationEngineContext,
     bg: Entity,
@@ -31,14 +70,11 @@ struct TitleScene<'a> {
     part_10: Entity,
     part_11: Entity,
     cover: Entity,
-    text_game_start: Entity,
-    text_monster_book: Entity,
-    text_user_guide: Entity,
-    text_options: Entity,
-    text_exit: Entity,
+    /// 上から順に並べたメニューの項目と表示位置
+    menu: Vec<(TitleMenu, Entity, f32, f32)>,
 }
 impl<'a> TitleScene<'a> {
-    fn new(cx: &'a AnimationEngineContext) -> Self {
+    fn new(cx: &'a AnimationEngineContext, can_continue: bool) -> Self {
         trace!("new title scene.");
 
         let bg = cx.add_image(AddImageInfo {
@@ -121,66 +157,41 @@ impl<'a> TitleScene<'a> {
             z: 390,
             ..Default::default()
         });
-        let text_game_start = cx.add_text(AddTextInfo {
-            key: "game-start".into(),
-            font_size: 36.0,
-            x: 160.0 + 8.816349 * 4.0,
-            y: 375.0,
-            z: 350,
-            r: 212.0 / 255.0,
-            g: 1.0,
-            b: 1.0,
-            a: 0.0,
-            ..Default::default()
-        });
-        let text_monster_book = cx.add_text(AddTextInfo {
-            key: "monster-book".into(),
-            font_size: 36.0,
-            x: 160.0 + 8.816349 * 3.0,
-            y: 425.0,
-            z: 350,
-            r: 212.0 / 255.0,
-            g: 1.0,
-            b: 1.0,
-            a: 0.0,
-            ..Default::default()
-        });
-        let text_user_guide = cx.add_text(AddTextInfo {
-            key: "user-guide".into(),
-            font_size: 36.0,
-            x: 160.0 + 8.816349 * 2.0,
-            y: 475.0,
-            z: 350,
-            r: 212.0 / 255.0,
-            g: 1.0,
-            b: 1.0,
-            a: 0.0,
-            ..Default::default()
-        });
-        let text_options = cx.add_text(AddTextInfo {
-            key: "options".into(),
-            font_size: 36.0,
-            x: 160.0 + 8.816349,
-            y: 525.0,
-            z: 350,
-            r: 212.0 / 255.0,
-            g: 1.0,
-            b: 1.0,
-            a: 0.0,
-            ..Default::default()
-        });
-        let text_exit = cx.add_text(AddTextInfo {
-            key: "exit".into(),
-            font_size: 36.0,
-            x: 160.0,
-            y: 575.0,
-            z: 350,
-            r: 212.0 / 255.0,
-            g: 1.0,
-            b: 1.0,
-            a: 0.0,
-            ..Default::default()
-        });
+        let mut menu_items = vec![];
+        if can_continue {
+            menu_items.push(TitleMenu::Continue);
+        }
+        menu_items.extend([
+            TitleMenu::GameStart,
+            TitleMenu::MonsterBook,
+            TitleMenu::Statistics,
+            TitleMenu::UserGuide,
+            TitleMenu::Options,
+            TitleMenu::Exit,
+        ]);
+        let menu_len = menu_items.len();
+        let menu = menu_items
+            .into_iter()
+            .enumerate()
+            .map(|(i, item)| {
+                let row = (menu_len - 1 - i) as f32;
+                let x = MENU_X + MENU_SLANT * row;
+                let y = MENU_Y - MENU_LINE_HEIGHT * row;
+                let text = cx.add_text(AddTextInfo {
+                    key: item.text_key().into(),
+                    font_size: 36.0,
+                    x,
+                    y,
+                    z: 350,
+                    r: 212.0 / 255.0,
+                    g: 1.0,
+                    b: 1.0,
+                    a: 0.0,
+                    ..Default::default()
+                });
+                (item, text, x, y)
+            })
+            .collect();
         Self {
             cx,
             bg,
@@ -198,11 +209,7 @@ impl<'a> TitleScene<'a> {
             part_10,
             part_11,
             cover,
-            text_game_start,
-            text_monster_book,
-            text_user_guide,
-            text_options,
-            text_exit,
+            menu,
         }
     }
 
@@ -243,18 +250,16 @@ impl<'a> TitleScene<'a> {
                 .play_animation(self.part_10, "/animation/title/part-10-enter.yml"),
             self.cx
                 .play_animation(self.part_11, "/animation/title/part-11-enter.yml"),
-            self.cx.play_animation(
-                self.text_game_start,
-                "/animation/title/menu-selected-enter.yml"
-            ),
-            self.cx
-                .play_animation(self.text_monster_book, "/animation/title/menu-enter.yml"),
-            self.cx
-                .play_animation(self.text_user_guide, "/animation/title/menu-enter.yml"),
-            self.cx
-                .play_animation(self.text_options, "/animation/title/menu-enter.yml"),
-            self.cx
-                .play_animation(self.text_exit, "/animation/title/menu-enter.yml"),
+            try_join_all(self.menu.iter().enumerate().map(|(i, &(_, text, _, _))| {
+                self.cx.play_animation(
+                    text,
+                    if i == 0 {
+                        "/animation/title/menu-selected-enter.yml"
+                    } else {
+                        "/animation/title/menu-enter.yml"
+                    },
+                )
+            })),
         )
         .expect("animation not found");
     }
@@ -271,111 +276,69 @@ impl<'a> TitleScene<'a> {
         self.cx.play_sfx("/audio/sfx/menu.ogg");
         self.enter_animation().await;
 
+        let menu_len = self.menu.len();
         let mut index = 0;
         loop {
-            match index {
-                0 => {
-                    self.cx
-                        .set_position(self.part_6, 173.0, 370.0, 340)
-                        .unwrap();
-                    self.cx
-                        .set_position(self.part_7, 173.0, 370.0, 360)
-                        .unwrap();
-                    self.cx.set_opacity(self.text_game_start, 1.0).unwrap();
-                    self.cx.set_opacity(self.text_monster_book, 0.2).unwrap();
-                    self.cx.set_opacity(self.text_user_guide, 0.2).unwrap();
-                    self.cx.set_opacity(self.text_options, 0.2).unwrap();
-                    self.cx.set_opacity(self.text_exit, 0.2).unwrap();
-                }
-                1 => {
-                    self.cx
-                        .set_position(self.part_6, 173.0 - 8.816349, 420.0, 340)
-                        .unwrap();
-                    self.cx
-                        .set_position(self.part_7, 173.0 - 8.816349, 420.0, 360)
-                        .unwrap();
-                    self.cx.set_opacity(self.text_game_start, 0.2).unwrap();
-                    self.cx.set_opacity(self.text_monster_book, 1.5).unwrap();
-                    self.cx.set_opacity(self.text_user_guide, 0.2).unwrap();
-                    self.cx.set_opacity(self.text_options, 0.2).unwrap();
-                    self.cx.set_opacity(self.text_exit, 0.2).unwrap();
-                }
-                2 => {
-                    self.cx
-                        .set_position(self.part_6, 173.0 - 8.816349 * 2.0, 470.0, 340)
-                        .unwrap();
-                    self.cx
-                        .set_position(self.part_7, 173.0 - 8.816349 * 2.0, 470.0, 360)
-                        .unwrap();
-                    self.cx.set_opacity(self.text_game_start, 0.2).unwrap();
-                    self.cx.set_opacity(self.text_monster_book, 0.2).unwrap();
-                    self.cx.set_opacity(self.text_user_guide, 1.0).unwrap();
-                    self.cx.set_opacity(self.text_options, 0.2).unwrap();
-                    self.cx.set_opacity(self.text_exit, 0.2).unwrap();
-                }
-                3 => {
-                    self.cx
-                        .set_position(self.part_6, 173.0 - 8.816349 * 3.0, 520.0, 340)
-                        .unwrap();
-                    self.cx
-                        .set_position(self.part_7, 173.0 - 8.816349 * 3.0, 520.0, 360)
-                        .unwrap();
-                    self.cx.set_opacity(self.text_game_start, 0.2).unwrap();
-                    self.cx.set_opacity(self.text_monster_book, 0.2).unwrap();
-                    self.cx.set_opacity(self.text_user_guide, 0.2).unwrap();
-                    self.cx.set_opacity(self.text_options, 1.0).unwrap();
-                    self.cx.set_opacity(self.text_exit, 0.2).unwrap();
-                }
-                4 => {
-                    self.cx
-                        .set_position(self.part_6, 173.0 - 8.816349 * 4.0, 570.0, 340)
-                        .unwrap();
-                    self.cx
-                        .set_position(self.part_7, 173.0 - 8.816349 * 4.0, 570.0, 360)
-                        .unwrap();
-                    self.cx.set_opacity(self.text_game_start, 0.2).unwrap();
-                    self.cx.set_opacity(self.text_monster_book, 0.2).unwrap();
-                    self.cx.set_opacity(self.text_user_guide, 0.2).unwrap();
-                    self.cx.set_opacity(self.text_options, 0.2).unwrap();
-                    self.cx.set_opacity(self.text_exit, 1.0).unwrap();
-                }
-                _ => unreachable!(),
+            let (_, _, x, y) = self.menu[index];
+            let (cursor_x, cursor_y) = (x + CURSOR_OFFSET_X, y + CURSOR_OFFSET_Y);
+            self.cx
+                .set_position(self.part_6, cursor_x, cursor_y, 340)
+                .unwrap();
+            self.cx
+                .set_position(self.part_7, cursor_x, cursor_y, 360)
+                .unwrap();
+            for (i, &(_, text, _, _)) in self.menu.iter().enumerate() {
+                self.cx
+                    .set_opacity(text, if i == index { 1.0 } else { 0.2 })
+                    .unwrap();
             }
             select! {
                 _ = input::wait_up(self.cx).fuse() => {
-                    index = (index - 1 + 5) % 5;
+                    index = (index + menu_len - 1) % menu_len;
                     self.cx.play_sfx("/audio/sfx/cursor.ogg");
                     delay(Duration::from_millis(150)).await;
                 }
                 _ = input::wait_down(self.cx).fuse() => {
-                    index = (index + 1 + 5) % 5;
+                    index = (index + 1) % menu_len;
                     self.cx.play_sfx("/audio/sfx/cursor.ogg");
                     delay(Duration::from_millis(150)).await;
                 }
                 _ = input::wait_select_button(self.cx).fuse() => {
-                    match index {
-                        0 => {
+                    let (item, _, _, _) = self.menu[index];
+                    match item {
+                        TitleMenu::Continue => {
                             self.cx.play_sfx("/audio/sfx/select.ogg");
                             self.fade_animation().await;
-                            return TitleResult::StartGame;
+                            return TitleResult::Continue;
                         },
-                        1 => {
+                        TitleMenu::GameStart => {
                             self.cx.play_sfx("/audio/sfx/select.ogg");
+                            if let Some(seed) = run_setup::run_setup(self.cx).await {
+                                self.fade_animation().await;
+                                return TitleResult::StartGame(seed);
+                            }
                         },
-                        2 => {
+                        TitleMenu::MonsterBook => {
+                            self.cx.play_sfx("/audio/sfx/select.ogg");
+                            monster_book::monster_book(self.cx, global_data).await;
+                        },
+                        TitleMenu::Statistics => {
+                            self.cx.play_sfx("/audio/sfx/select.ogg");
+                            statistics::statistics(self.cx, global_data).await;
+                        }
+                        TitleMenu::UserGuide => {
                             self.cx.play_sfx("/audio/sfx/select.ogg");
                             user_guide::user_guide(self.cx).await
                         }
-                        3 => {
+                        TitleMenu::Options => {
                             self.cx.play_sfx("/audio/sfx/select.ogg");
                             options::options(self.cx, &mut global_data.save_data).await;
                         }
-                        4 => {
+                        TitleMenu::Exit => {
                             self.cx.play_sfx("/audio/sfx/cancel.ogg");
                             self.fade_animation().await;
                             return TitleResult::Exit;
                         },
-                        _ => unreachable!(),
                     }
                 }
                 _ = input::wait_sub_button(self.cx).fuse() => {
@@ -413,15 +376,14 @@ impl<'a> Drop for TitleScene<'a> {
         self.cx.delete_entity(self.part_10);
         self.cx.delete_entity(self.part_11);
         self.cx.delete_entity(self.cover);
-        self.cx.delete_entity(self.text_game_start);
-        self.cx.delete_entity(self.text_monster_book);
-        self.cx.delete_entity(self.text_user_guide);
-        self.cx.delete_entity(self.text_options);
-        self.cx.delete_entity(self.text_exit);
+        for &(_, text, _, _) in self.menu.iter() {
+            self.cx.delete_entity(text);
+        }
     }
 }
 
 pub async fn title(cx: &AnimationEngineContext, global_data: &mut game::GlobalData) -> TitleResult {
     info!("Enter Title Scene!");
-    TitleScene::new(cx).start(global_data).await
+    let can_continue = global_data.save_data.suspend_data().is_some();
+    TitleScene::new(cx, can_continue).start(global_data).await
 }
@@ -1,11 +1,21 @@
+mod credits_data;
+mod effect_data;
+mod ending_data;
+mod enemy_data;
 mod game_data;
 mod item_data;
 mod level_data;
 mod opening_data;
 mod player_data;
+mod validation;
 
+pub use credits_data::*;
+pub use effect_data::*;
+pub use ending_data::*;
+pub use enemy_data::*;
 pub use game_data::*;
 pub use item_data::*;
 pub use level_data::*;
 pub use opening_data::OpeningData;
 pub use player_data::*;
+pub use validation::*;
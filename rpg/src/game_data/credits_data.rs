@@ -0,0 +1,18 @@
+use serde::Deserialize;
+
+/// textはすべてi18nのキー
+#[derive(Deserialize)]
+pub enum CreditsLine {
+    Title { text: String },
+    Heading { text: String },
+    Name { text: String },
+    Space,
+}
+
+#[derive(Deserialize)]
+pub struct CreditsData {
+    pub bgm: Option<String>,
+    /// 1フレームあたりのスクロール量
+    pub scroll_speed: f32,
+    pub lines: Vec<CreditsLine>,
+}
@@ -0,0 +1,67 @@
+use serde::Deserialize;
+
+#[derive(Deserialize, PartialEq, Eq, Debug, Clone, Copy, Hash)]
+pub enum ModifierKind {
+    Poison,
+    Regen,
+    AttackUp,
+    AttackDown,
+    DefenseUp,
+    DefenseDown,
+    Guard,
+    SureHit,
+}
+
+/// スキルの属性。敵ごとに属性ごとのダメージ倍率を持てる
+#[derive(Deserialize, PartialEq, Eq, Debug, Clone, Copy, Hash)]
+pub enum Element {
+    Slash,
+    Strike,
+    Fire,
+    Ice,
+    Thunder,
+}
+
+/// スキルやアイテムの効果。
+/// Damage/MultiHit/Debuffは使用者の相手に、Heal/HealRatio/GainTp/Buff/Cureは使用者自身に作用する。
+/// SetState/AddStateは戦闘中の状態(BattleStateStore)を書き換える。
+#[derive(Deserialize, PartialEq, Debug, Clone)]
+pub enum Effect {
+    Damage {
+        multiplier: f64,
+    },
+    MultiHit {
+        multiplier: f64,
+        hits: u32,
+    },
+    Heal {
+        amount: i32,
+    },
+    HealRatio {
+        ratio: f64,
+    },
+    GainTp {
+        amount: i32,
+    },
+    Buff {
+        modifier: ModifierKind,
+        value: i32,
+        turns: u32,
+    },
+    Debuff {
+        modifier: ModifierKind,
+        value: i32,
+        turns: u32,
+    },
+    Cure {
+        modifiers: Vec<ModifierKind>,
+    },
+    SetState {
+        key: String,
+        value: i32,
+    },
+    AddState {
+        key: String,
+        value: i32,
+    },
+}
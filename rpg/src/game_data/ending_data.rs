@@ -0,0 +1,14 @@
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct PlayerEnding {
+    pub player_index: usize,
+    pub messages: Vec<String>,
+}
+
+/// ゲームクリア時に選択したプレイヤーに応じて再生するエンディング
+#[derive(Deserialize)]
+pub struct EndingData {
+    pub bgm: String,
+    pub endings: Vec<PlayerEnding>,
+}
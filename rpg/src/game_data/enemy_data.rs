@@ -0,0 +1,104 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::game_data::*;
+
+#[derive(Deserialize, Serialize, PartialEq, Eq, Debug, Clone, Copy, Hash)]
+pub struct EnemyId(pub usize);
+
+#[derive(Deserialize)]
+pub struct EnemyAction {
+    pub message: String,
+    pub effects: Vec<Effect>,
+}
+
+/// 条件で参照するHPは最大HPに対する割合、stateはBattleStateStoreのキー。
+#[derive(Deserialize)]
+pub enum EnemyActionCondition {
+    HpBelow { ratio: f64 },
+    PlayerHpBelow { ratio: f64 },
+    StateEquals { key: String, value: i32 },
+    StateAtLeast { key: String, value: i32 },
+    StateBelow { key: String, value: i32 },
+}
+
+#[derive(Deserialize)]
+pub struct WeightedEnemyAction {
+    pub weight: f64,
+    pub pattern: EnemyActionPattern,
+}
+
+#[derive(Deserialize)]
+pub struct ConditionalEnemyAction {
+    pub condition: EnemyActionCondition,
+    pub pattern: EnemyActionPattern,
+}
+
+#[derive(Deserialize)]
+pub enum EnemyActionPattern {
+    Action(EnemyAction),
+    Weighted {
+        branches: Vec<WeightedEnemyAction>,
+    },
+    Conditional {
+        branches: Vec<ConditionalEnemyAction>,
+        otherwise: Box<EnemyActionPattern>,
+    },
+    Rotation {
+        key: String,
+        patterns: Vec<EnemyActionPattern>,
+    },
+}
+
+/// 敵のHP割合がhp_belowを下回るとaction_patternが切り替わる。
+#[derive(Deserialize)]
+pub struct EnemyPhase {
+    pub hp_below: f64,
+    pub message: String,
+    pub action_pattern: EnemyActionPattern,
+}
+
+/// item_idを省略した枠は「何も落とさない」を表す
+#[derive(Deserialize)]
+pub struct ItemDrop {
+    pub weight: f64,
+    pub item_id: Option<ItemId>,
+    pub count: Option<u32>,
+}
+
+#[derive(Deserialize)]
+pub struct SkillDrop {
+    pub skills: Vec<SkillRarityWeight>,
+    pub count: u8,
+}
+
+/// 勝利時の報酬。itemsはrollsの回数だけ重み付きで抽選する
+#[derive(Deserialize)]
+pub struct DropTable {
+    pub gold: Option<u32>,
+    pub items: Option<Vec<ItemDrop>>,
+    pub rolls: Option<u32>,
+    pub skills: Option<SkillDrop>,
+}
+
+#[derive(Deserialize)]
+pub struct EnemyData {
+    pub id: EnemyId,
+    pub enemy_name: String,
+    pub description: String,
+    pub image: String,
+    pub shadow_image: String,
+    pub bark_sfx: String,
+    pub max_hp: i32,
+    pub attack: i32,
+    pub defense: i32,
+    pub critical_rate: Option<f64>,
+    pub evasion_rate: Option<f64>,
+    pub element_rates: Option<HashMap<Element, f64>>,
+    pub action_pattern: EnemyActionPattern,
+    pub boss: Option<bool>,
+    pub cannot_escape: Option<bool>,
+    pub bgm: Option<String>,
+    pub phases: Option<Vec<EnemyPhase>>,
+    pub drops: Option<DropTable>,
+}
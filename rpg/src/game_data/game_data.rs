@@ -1,50 +1,102 @@
 use animation_engine::*;
+use serde::de::DeserializeOwned;
+use std::fs;
 use std::io;
+use std::path::{Path, PathBuf};
 
 use crate::game_data::*;
 
+pub(super) trait GameDataSource {
+    fn open(&mut self, path: &Path) -> anyhow::Result<Box<dyn io::Read>>;
+    fn read_dir(&mut self, path: &str) -> anyhow::Result<Vec<PathBuf>>;
+
+    fn read_yaml<T: DeserializeOwned>(&mut self, path: impl AsRef<Path>) -> anyhow::Result<T> {
+        let reader = io::BufReader::new(self.open(path.as_ref())?);
+        Ok(serde_yaml::from_reader(reader)?)
+    }
+}
+
+struct EngineSource<'a>(&'a mut AnimationEngine);
+impl<'a> GameDataSource for EngineSource<'a> {
+    fn open(&mut self, path: &Path) -> anyhow::Result<Box<dyn io::Read>> {
+        Ok(Box::new(self.0.filesystem().open(path)?))
+    }
+
+    fn read_dir(&mut self, path: &str) -> anyhow::Result<Vec<PathBuf>> {
+        Ok(self.0.filesystem().read_dir(path)?.collect())
+    }
+}
+
+/// パスはエンジンと同じく"/"始まりのリソースルートからの相対パスで扱う
+pub(super) struct DirSource(pub(super) PathBuf);
+impl GameDataSource for DirSource {
+    fn open(&mut self, path: &Path) -> anyhow::Result<Box<dyn io::Read>> {
+        let path = path.strip_prefix("/").unwrap_or(path);
+        Ok(Box::new(fs::File::open(self.0.join(path))?))
+    }
+
+    fn read_dir(&mut self, path: &str) -> anyhow::Result<Vec<PathBuf>> {
+        let mut paths = vec![];
+        for entry in fs::read_dir(self.0.join(path.trim_start_matches('/')))? {
+            paths.push(Path::new(path).join(entry?.file_name()));
+        }
+        Ok(paths)
+    }
+}
+
 pub struct GameData {
     opening_data: OpeningData,
+    ending_data: EndingData,
+    credits_data: CreditsData,
     player_data: Vec<PlayerData>,
     item_data: Vec<ItemData>,
     level_data: Vec<LevelData>,
+    enemy_data: Vec<EnemyData>,
 }
 impl GameData {
     pub fn load(engine: &mut AnimationEngine) -> anyhow::Result<Self> {
-        let opening_data: OpeningData = {
-            let file = engine.filesystem().open("/game_data/opening-data.yml")?;
-            let reader = io::BufReader::new(file);
-            serde_yaml::from_reader(reader)?
-        };
+        Self::load_from(&mut EngineSource(engine))
+    }
+
+    /// エンジンを使わずにリソースディレクトリから直接読み込む
+    pub fn load_from_dir(resources_dir: impl AsRef<Path>) -> anyhow::Result<Self> {
+        Self::load_from(&mut DirSource(resources_dir.as_ref().to_path_buf()))
+    }
+
+    fn load_from(source: &mut impl GameDataSource) -> anyhow::Result<Self> {
+        let opening_data: OpeningData = source.read_yaml("/game_data/opening-data.yml")?;
+        let ending_data: EndingData = source.read_yaml("/game_data/ending-data.yml")?;
+        let credits_data: CreditsData = source.read_yaml("/game_data/credits-data.yml")?;
 
         let mut player_data = vec![];
-        for path in engine.filesystem().read_dir("/game_data/player-data/")? {
-            let file = engine.filesystem().open(path)?;
-            let reader = io::BufReader::new(file);
-            player_data.push(serde_yaml::from_reader(reader)?);
+        for path in source.read_dir("/game_data/player-data/")? {
+            player_data.push(source.read_yaml(&path)?);
         }
         player_data.sort_by_cached_key(|p: &PlayerData| p.index);
 
-        let mut item_data: Vec<ItemData> = {
-            let file = engine.filesystem().open("/game_data/item-data.yml")?;
-            let reader = io::BufReader::new(file);
-            serde_yaml::from_reader(reader)?
-        };
+        let mut item_data: Vec<ItemData> = source.read_yaml("/game_data/item-data.yml")?;
         item_data.sort_by_cached_key(|i: &ItemData| i.id.0);
 
         let mut level_data = vec![];
-        for path in engine.filesystem().read_dir("/game_data/level-data/")? {
-            let file = engine.filesystem().open(path)?;
-            let reader = io::BufReader::new(file);
-            level_data.push(serde_yaml::from_reader(reader)?);
+        for path in source.read_dir("/game_data/level-data/")? {
+            level_data.push(source.read_yaml(&path)?);
         }
         level_data.sort_by_cached_key(|l: &LevelData| l.index);
 
+        let mut enemy_data = vec![];
+        for path in source.read_dir("/game_data/enemy-data/")? {
+            enemy_data.push(source.read_yaml(&path)?);
+        }
+        enemy_data.sort_by_cached_key(|e: &EnemyData| e.id.0);
+
         Ok(Self {
             opening_data,
+            ending_data,
+            credits_data,
             player_data,
             item_data,
             level_data,
+            enemy_data,
         })
     }
 
@@ -52,6 +104,14 @@ impl GameData {
         &self.opening_data
     }
 
+    pub fn ending_data(&self) -> &EndingData {
+        &self.ending_data
+    }
+
+    pub fn credits_data(&self) -> &CreditsData {
+        &self.credits_data
+    }
+
     pub fn player_data(&self) -> &Vec<PlayerData> {
         &self.player_data
     }
@@ -63,4 +123,175 @@ impl GameData {
     pub fn level_data(&self) -> &Vec<LevelData> {
         &self.level_data
     }
+
+    pub fn enemy_data(&self) -> &Vec<EnemyData> {
+        &self.enemy_data
+    }
+
+    pub fn level_index(&self, id: &str) -> Option<usize> {
+        self.level_data
+            .iter()
+            .position(|l| l.id.as_deref() == Some(id))
+    }
+
+    /// index番目のレベルを終えた後に進みうるレベル。空ならゲームクリア
+    pub fn next_levels(&self, index: usize) -> Vec<usize> {
+        let level = &self.level_data[index];
+        let mut next_levels = vec![];
+        match &level.next {
+            Some(next) => next_levels.extend(self.level_index(next)),
+            None if index + 1 < self.level_data.len() => next_levels.push(index + 1),
+            None => (),
+        }
+        let mut jumps = vec![];
+        level.item.collect_jumps(&mut jumps);
+        for jump in jumps {
+            match jump {
+                LevelItem::JumpTo { level } => next_levels.extend(self.level_index(level)),
+                LevelItem::Skip { count } if index + 1 + count < self.level_data.len() => {
+                    next_levels.push(index + 1 + count)
+                }
+                _ => (),
+            }
+        }
+        next_levels
+    }
+
+    /// 最初のレベルからゲームクリアまでに通りうる最も長い経路の深さ。戻るジャンプは数えない
+    pub fn max_depth(&self) -> u32 {
+        fn longest(
+            game_data: &GameData,
+            index: usize,
+            memo: &mut Vec<Option<u32>>,
+            visiting: &mut Vec<bool>,
+        ) -> u32 {
+            if let Some(depth) = memo[index] {
+                return depth;
+            }
+            if visiting[index] {
+                return 0;
+            }
+            visiting[index] = true;
+            let depth = 1 + game_data
+                .next_levels(index)
+                .into_iter()
+                .map(|next| longest(game_data, next, memo, visiting))
+                .max()
+                .unwrap_or(0);
+            visiting[index] = false;
+            memo[index] = Some(depth);
+            depth
+        }
+
+        if self.level_data.is_empty() {
+            return 0;
+        }
+        let len = self.level_data.len();
+        longest(self, 0, &mut vec![None; len], &mut vec![false; len])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 同梱のデータを読み込み、レベルだけを差し替える
+    fn game_data_with_levels(levels: &[&str]) -> GameData {
+        let mut game_data =
+            GameData::load_from_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/resources")).unwrap();
+        game_data.level_data = levels
+            .iter()
+            .map(|level| serde_yaml::from_str(level).unwrap())
+            .collect();
+        game_data
+    }
+
+    /// JumpToと何もしない項目のどちらかを通るレベル
+    fn fork_to(index: u32, id: &str, target: &str) -> String {
+        format!(
+            "
+index: {}
+id: {}
+item:
+  Random:
+    branches:
+      - weight: 1.0
+        item:
+          JumpTo:
+            level: {}
+      - weight: 1.0
+        item: StopBGM
+",
+            index, id, target
+        )
+    }
+
+    #[test]
+    fn it_should_count_levels_in_order() {
+        let game_data = game_data_with_levels(&[
+            "{index: 0, item: StopBGM}",
+            "{index: 1, item: StopBGM}",
+            "{index: 2, item: StopBGM}",
+        ]);
+        assert_eq!(game_data.max_depth(), 3);
+        assert_eq!(game_data.next_levels(2), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn it_should_follow_next_instead_of_index_order() {
+        let game_data = game_data_with_levels(&[
+            "{index: 0, next: d, item: StopBGM}",
+            "{index: 1, item: StopBGM}",
+            "{index: 2, item: StopBGM}",
+            "{index: 3, id: d, item: StopBGM}",
+        ]);
+        assert_eq!(game_data.next_levels(0), vec![3]);
+        assert_eq!(game_data.max_depth(), 2);
+    }
+
+    #[test]
+    fn it_should_take_longest_route_of_fork() {
+        let fork = fork_to(0, "a", "d");
+        let game_data = game_data_with_levels(&[
+            &fork,
+            "{index: 1, item: StopBGM}",
+            "{index: 2, item: StopBGM}",
+            "{index: 3, id: d, item: StopBGM}",
+        ]);
+        assert_eq!(game_data.next_levels(0), vec![1, 3]);
+        assert_eq!(game_data.max_depth(), 4);
+    }
+
+    #[test]
+    fn it_should_count_skip_as_fork() {
+        let game_data = game_data_with_levels(&[
+            "{index: 0, item: {Skip: {count: 1}}}",
+            "{index: 1, item: StopBGM}",
+            "{index: 2, item: StopBGM}",
+        ]);
+        assert_eq!(game_data.next_levels(0), vec![1, 2]);
+        assert_eq!(game_data.max_depth(), 3);
+    }
+
+    #[test]
+    fn it_should_not_loop_on_backward_jump() {
+        let fork = fork_to(2, "c", "a");
+        let game_data = game_data_with_levels(&[
+            "{index: 0, id: a, item: StopBGM}",
+            "{index: 1, item: StopBGM}",
+            &fork,
+            "{index: 3, item: StopBGM}",
+        ]);
+        assert_eq!(game_data.next_levels(2), vec![3, 0]);
+        assert_eq!(game_data.max_depth(), 4);
+    }
+
+    #[test]
+    fn it_should_not_loop_on_cycle_without_exit() {
+        let game_data = game_data_with_levels(&[
+            "{index: 0, id: a, item: StopBGM}",
+            "{index: 1, next: a, item: StopBGM}",
+        ]);
+        assert_eq!(game_data.max_depth(), 2);
+    }
 }
@@ -1,6 +1,8 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Deserialize, PartialEq, Eq, Debug, Clone, Copy, Hash)]
+use crate::game_data::*;
+
+#[derive(Deserialize, Serialize, PartialEq, Eq, Debug, Clone, Copy, Hash)]
 pub struct ItemId(pub usize);
 
 #[derive(Deserialize, PartialEq, Eq, Debug, Clone, Copy)]
@@ -16,4 +18,6 @@ pub struct ItemData {
     pub item_name_with_count: String,
     pub item_description: String,
     pub item_target: ItemTarget,
+    pub effects: Vec<Effect>,
+    pub use_on_down: Option<bool>,
 }
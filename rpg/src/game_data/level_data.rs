@@ -1,17 +1,46 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 use crate::game_data::*;
 
+/// 1回の戦闘に出せる敵の数の上限(idとcompanionsの合計)。戦闘画面はこの数までしか並べられない
+pub const MAX_ENEMIES: usize = 3;
+
+/// カウンタの値にweightを掛けた分だけ分岐の重みを増やす
+#[derive(Deserialize)]
+pub struct CounterWeight {
+    pub counter: String,
+    pub weight: f64,
+}
+
+/// conditionを満たさない分岐は選ばれない
 #[derive(Deserialize)]
 pub struct RandomBranch {
     pub weight: f64,
+    pub condition: Option<LevelCondition>,
+    pub counter_weight: Option<CounterWeight>,
     pub item: Box<LevelItem>,
 }
 
+/// 選択肢や交換で支払うもの
+#[derive(Deserialize)]
+pub enum Cost {
+    Item { item_id: ItemId, count: u32 },
+    Gold { amount: u32 },
+}
+
+#[derive(Deserialize)]
+pub struct ItemCount {
+    pub item_id: ItemId,
+    pub count: u32,
+}
+
+/// conditionを満たさないか、costsを支払えない選択肢は選べない状態で表示される
 #[derive(Deserialize)]
 pub struct ChoiceBranch {
     pub text: String,
     pub text_lines: u8,
+    pub condition: Option<LevelCondition>,
+    pub costs: Option<Vec<Cost>>,
     pub item: Box<LevelItem>,
 }
 
@@ -21,13 +50,51 @@ pub struct SkillRarityWeight {
     pub rarity: u8,
 }
 
-#[derive(Deserialize, Clone, Copy)]
+#[derive(Deserialize, Serialize, PartialEq, Eq, Debug, Clone, Copy)]
 pub enum BattleTime {
     Morning,
     Afternoon,
     Night,
 }
 
+/// Persistentのフラグとカウンタはセーブデータに保存され、次回以降の探索にも引き継がれる
+#[derive(Deserialize, PartialEq, Eq, Debug, Clone, Copy)]
+pub enum FlagScope {
+    Run,
+    Persistent,
+}
+
+/// メッセージの"{}"に順番に埋め込む値。Flagはフラグの状態に応じたテキストのキーを選ぶ
+#[derive(Deserialize)]
+pub enum MessageArg {
+    Counter {
+        counter: String,
+    },
+    Flag {
+        flag: String,
+        set: String,
+        unset: String,
+    },
+}
+
+/// LevelItem::Ifの条件。HPは最大HPに対する割合、深さは1始まりで数える
+#[derive(Deserialize)]
+pub enum LevelCondition {
+    HasItem { item_id: ItemId, count: Option<u32> },
+    HasSkill { skill_id: SkillId },
+    HasSkillType { skill_type: usize },
+    HpBelow { ratio: f64 },
+    FlagSet { flag: String },
+    CounterAtLeast { counter: String, value: i32 },
+    CounterBelow { counter: String, value: i32 },
+    Time { time: BattleTime },
+    DepthAtLeast { depth: u32 },
+    DepthBelow { depth: u32 },
+    Not { condition: Box<LevelCondition> },
+    All { conditions: Vec<LevelCondition> },
+    Any { conditions: Vec<LevelCondition> },
+}
+
 #[derive(Deserialize)]
 pub enum LevelItem {
     Sequence {
@@ -38,6 +105,7 @@ pub enum LevelItem {
     },
     Message {
         text: String,
+        args: Option<Vec<MessageArg>>,
         blue: Option<bool>,
         no_weight: Option<bool>,
     },
@@ -45,6 +113,12 @@ pub enum LevelItem {
         text: String,
         branches: Vec<ChoiceBranch>,
     },
+    If {
+        condition: LevelCondition,
+        then: Box<LevelItem>,
+        #[serde(rename = "else")]
+        otherwise: Option<Box<LevelItem>>,
+    },
     PlayBGM {
         bgm: String,
     },
@@ -55,9 +129,11 @@ pub enum LevelItem {
     ChangeToAfternoon,
     ChangeToNight,
     Battle {
-        id: usize,
+        id: EnemyId,
         bgm: String,
         time: BattleTime,
+        companions: Option<Vec<EnemyId>>,
+        escape_penalty: Option<Box<LevelItem>>,
     },
     WaitOpenSkillItemList,
     GetSkill {
@@ -68,10 +144,100 @@ pub enum LevelItem {
         item_id: ItemId,
         count: usize,
     },
+    RemoveItem {
+        item_id: ItemId,
+        count: usize,
+    },
+    /// giveをすべて支払えた場合だけreceiveを受け取ってthenに進み、足りなければelseに進む
+    Trade {
+        give: Vec<Cost>,
+        receive: Vec<ItemCount>,
+        then: Option<Box<LevelItem>>,
+        #[serde(rename = "else")]
+        otherwise: Option<Box<LevelItem>>,
+    },
+    Heal {
+        amount: i32,
+    },
+    Damage {
+        amount: i32,
+    },
+    RestoreTp {
+        amount: i32,
+    },
+    SetFlag {
+        flag: String,
+        scope: Option<FlagScope>,
+    },
+    ClearFlag {
+        flag: String,
+        scope: Option<FlagScope>,
+    },
+    AddCounter {
+        counter: String,
+        amount: i32,
+        scope: Option<FlagScope>,
+    },
+    /// 現在のレベルの残りを飛ばし、idで指定したレベルに進む
+    JumpTo {
+        level: String,
+    },
+    /// 現在のレベルの残りを飛ばし、count個先のレベルに進む
+    Skip {
+        count: usize,
+    },
+}
+impl LevelItem {
+    /// 入れ子になった項目も含め、通りうるJumpTo/Skipをすべて集める
+    pub fn collect_jumps<'a>(&'a self, jumps: &mut Vec<&'a LevelItem>) {
+        match self {
+            LevelItem::Sequence { items } => {
+                for item in items.iter() {
+                    item.collect_jumps(jumps);
+                }
+            }
+            LevelItem::Random { branches } => {
+                for branch in branches.iter() {
+                    branch.item.collect_jumps(jumps);
+                }
+            }
+            LevelItem::Choice { branches, .. } => {
+                for branch in branches.iter() {
+                    branch.item.collect_jumps(jumps);
+                }
+            }
+            LevelItem::If {
+                then, otherwise, ..
+            } => {
+                then.collect_jumps(jumps);
+                if let Some(otherwise) = otherwise {
+                    otherwise.collect_jumps(jumps);
+                }
+            }
+            LevelItem::Battle {
+                escape_penalty: Some(escape_penalty),
+                ..
+            } => escape_penalty.collect_jumps(jumps),
+            LevelItem::Trade {
+                then, otherwise, ..
+            } => {
+                for item in then.iter().chain(otherwise.iter()) {
+                    item.collect_jumps(jumps);
+                }
+            }
+            LevelItem::JumpTo { .. } | LevelItem::Skip { .. } => jumps.push(self),
+            _ => (),
+        }
+    }
 }
 
+/// checkpointが立っている深さに入るとプレイヤーの状態が記録され、ゲームオーバー時にそこから再挑戦できる
+/// idはJumpToやnextから参照される名前。nextを省略したレベルの次はindex順で次のレベルになる
 #[derive(Deserialize)]
 pub struct LevelData {
     pub index: u32,
+    pub id: Option<String>,
+    pub next: Option<String>,
+    pub checkpoint: Option<bool>,
     pub item: LevelItem,
 }
@@ -1,4 +1,6 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+
+use crate::game_data::*;
 
 #[derive(Deserialize)]
 pub struct PrologueMessage(pub String);
@@ -9,7 +11,7 @@ pub struct PrologueIndex {
     pub messages: Vec<PrologueMessage>,
 }
 
-#[derive(Deserialize, PartialEq, Eq, Debug, Clone, Copy, Hash)]
+#[derive(Deserialize, Serialize, PartialEq, Eq, Debug, Clone, Copy, Hash)]
 pub struct SkillId(pub usize);
 
 #[derive(Deserialize, PartialEq, Eq, Debug, Clone, Copy)]
@@ -36,6 +38,8 @@ pub struct SkillData {
     pub get_skill_confirm_message: String,
     pub skill_cost: SkillCost,
     pub skill_target: SkillTarget,
+    pub element: Option<Element>,
+    pub effects: Vec<Effect>,
 }
 
 #[derive(Deserialize)]
@@ -43,6 +47,12 @@ pub struct PlayerData {
     pub index: usize,
     pub image: String,
     pub shadow_image: String,
+    pub max_hp: i32,
+    pub max_tp: i32,
+    pub attack: i32,
+    pub defense: i32,
+    pub critical_rate: Option<f64>,
+    pub evasion_rate: Option<f64>,
     pub opening_legendary_name: String,
     pub opening_introduction_text: String,
     pub prologue: Vec<PrologueIndex>,
@@ -0,0 +1,929 @@
+use serde::de::DeserializeOwned;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::game_data::{DirSource, GameDataSource};
+use crate::assets_load::BgmList;
+use crate::game_data::*;
+use crate::localization::LocalizeInfo;
+
+/// Warningはゲームは動くが直しておきたいもの(翻訳漏れなど)
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// 検証で見つかった問題。pathはファイル内の位置を"item.Sequence.items[2].Message.text"のように表す
+pub struct ValidationProblem {
+    pub severity: Severity,
+    pub file: String,
+    pub path: String,
+    pub message: String,
+}
+impl fmt::Display for ValidationProblem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.severity == Severity::Warning {
+            write!(f, "warning: ")?;
+        }
+        if self.path.is_empty() {
+            write!(f, "{}: {}", self.file, self.message)
+        } else {
+            write!(f, "{}: {}: {}", self.file, self.path, self.message)
+        }
+    }
+}
+
+/// opening-data.ymlのようにルートが列挙型のファイルでは親のパスが空になる
+fn child_path(path: &str, child: &str) -> String {
+    if path.is_empty() {
+        child.to_string()
+    } else {
+        format!("{}.{}", path, child)
+    }
+}
+
+struct Validator {
+    source: DirSource,
+    /// 原文(jp-original.yml)のキー。読み込めなかった場合はテキストを照合しない
+    texts: Option<HashSet<String>>,
+    bgm: Option<HashSet<String>>,
+    item_ids: HashSet<ItemId>,
+    enemy_ids: HashSet<EnemyId>,
+    player_indices: HashSet<usize>,
+    level_ids: HashSet<String>,
+    skill_ids: HashSet<SkillId>,
+    skill_types: HashSet<usize>,
+    skill_rarities: HashSet<u8>,
+    /// 検査中のレベルより後ろにあるレベルの数
+    remaining_levels: usize,
+    problems: Vec<ValidationProblem>,
+}
+impl Validator {
+    fn report(&mut self, file: &Path, path: impl Into<String>, message: impl Into<String>) {
+        self.push_problem(Severity::Error, file, path.into(), message.into());
+    }
+
+    fn warn(&mut self, file: &Path, path: impl Into<String>, message: impl Into<String>) {
+        self.push_problem(Severity::Warning, file, path.into(), message.into());
+    }
+
+    fn push_problem(&mut self, severity: Severity, file: &Path, path: String, message: String) {
+        self.problems.push(ValidationProblem {
+            severity,
+            file: file.display().to_string(),
+            path,
+            message,
+        });
+    }
+
+    fn read_yaml<T: DeserializeOwned>(&mut self, path: &Path) -> Option<T> {
+        match self.source.read_yaml(path) {
+            Ok(data) => Some(data),
+            Err(e) => {
+                self.report(path, "", format!("failed to load: {}", e));
+                None
+            }
+        }
+    }
+
+    fn read_yaml_dir<T: DeserializeOwned>(&mut self, path: &str) -> Vec<(PathBuf, T)> {
+        let mut paths = match self.source.read_dir(path) {
+            Ok(paths) => paths,
+            Err(e) => {
+                self.report(
+                    Path::new(path),
+                    "",
+                    format!("failed to read directory: {}", e),
+                );
+                return vec![];
+            }
+        };
+        paths.sort();
+        paths
+            .into_iter()
+            .filter_map(|path| self.read_yaml(&path).map(|data| (path, data)))
+            .collect()
+    }
+
+    fn resource_exists(&self, path: &str) -> bool {
+        self.source.0.join(path.trim_start_matches('/')).is_file()
+    }
+
+    fn load_i18n(&mut self, i18n_dir: &Path) {
+        let original_path = i18n_dir.join("jp-original.yml");
+        let original = match LocalizeInfo::load(&original_path) {
+            Ok(info) => info,
+            Err(e) => {
+                self.report(&original_path, "", format!("failed to load: {}", e));
+                return;
+            }
+        };
+        self.check_font(&original_path, &original.font_name);
+
+        let mut paths = match fs::read_dir(i18n_dir) {
+            Ok(entries) => entries
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| *path != original_path)
+                .collect::<Vec<_>>(),
+            Err(e) => {
+                self.report(i18n_dir, "", format!("failed to read directory: {}", e));
+                vec![]
+            }
+        };
+        paths.sort();
+
+        // 翻訳ファイルに無いキーは言語の切り替え後に落ちるが、翻訳が追いつくまでは警告に留める
+        for path in paths {
+            let info = match LocalizeInfo::load(&path) {
+                Ok(info) => info,
+                Err(e) => {
+                    self.report(&path, "", format!("failed to load: {}", e));
+                    continue;
+                }
+            };
+            self.check_font(&path, &info.font_name);
+
+            let mut missing = original
+                .texts
+                .keys()
+                .filter(|key| !info.texts.contains_key(*key))
+                .collect::<Vec<_>>();
+            missing.sort();
+            for key in missing {
+                self.warn(
+                    &path,
+                    format!("texts.{}", key),
+                    "missing translation of a key in jp-original.yml",
+                );
+            }
+
+            let mut unknown = info
+                .texts
+                .keys()
+                .filter(|key| !original.texts.contains_key(*key))
+                .collect::<Vec<_>>();
+            unknown.sort();
+            for key in unknown {
+                self.warn(
+                    &path,
+                    format!("texts.{}", key),
+                    "key is not defined in jp-original.yml",
+                );
+            }
+        }
+
+        self.texts = Some(original.texts.into_keys().collect());
+    }
+
+    fn load_bgm(&mut self) {
+        let file = Path::new("/audio/bgm/bgm-list.yml");
+        let list: BgmList = match self.read_yaml(file) {
+            Some(list) => list,
+            None => return,
+        };
+
+        let mut names = HashSet::new();
+        for (i, bgm) in list.0.iter().enumerate() {
+            if !names.insert(bgm.name.clone()) {
+                self.report(file, format!("[{}].name", i), "duplicate bgm name");
+            }
+            let path = Path::new("/audio/bgm/").join(&bgm.path);
+            if !self.resource_exists(&path.to_string_lossy()) {
+                self.report(
+                    file,
+                    format!("[{}].path", i),
+                    format!("bgm file not found: {}", path.display()),
+                );
+            }
+        }
+        self.bgm = Some(names);
+    }
+
+    fn check_font(&mut self, file: &Path, font_name: &str) {
+        if !self.resource_exists(font_name) {
+            self.report(
+                file,
+                "font-file",
+                format!("font file not found: {}", font_name),
+            );
+        }
+    }
+
+    fn check_text(&mut self, file: &Path, path: String, key: &str) {
+        if matches!(&self.texts, Some(texts) if !texts.contains(key)) {
+            self.report(
+                file,
+                path,
+                format!("text key not found in jp-original.yml: {}", key),
+            );
+        }
+    }
+
+    fn check_bgm(&mut self, file: &Path, path: String, name: &str) {
+        if matches!(&self.bgm, Some(bgm) if !bgm.contains(name)) {
+            self.report(
+                file,
+                path,
+                format!("bgm not found in bgm-list.yml: {}", name),
+            );
+        }
+    }
+
+    fn check_sfx(&mut self, file: &Path, path: String, sfx: &str) {
+        if !sfx.starts_with("/audio/sfx/") || !self.resource_exists(sfx) {
+            self.report(file, path, format!("sfx file not found: {}", sfx));
+        }
+    }
+
+    fn check_image(&mut self, file: &Path, path: String, image: &str) {
+        if !self.resource_exists(image) {
+            self.report(file, path, format!("image file not found: {}", image));
+        }
+    }
+
+    fn check_item_id(&mut self, file: &Path, path: String, id: ItemId) {
+        if !self.item_ids.contains(&id) {
+            self.report(file, path, format!("item id not found: {}", id.0));
+        }
+    }
+
+    fn check_enemy_id(&mut self, file: &Path, path: String, id: EnemyId) {
+        if !self.enemy_ids.contains(&id) {
+            self.report(file, path, format!("enemy id not found: {}", id.0));
+        }
+    }
+
+    /// WeightedIndexは負の重みや合計0の重みを受け付けない
+    fn check_weights(
+        &mut self,
+        file: &Path,
+        path: &str,
+        weights: &[f64],
+        weight_path: impl Fn(usize) -> String,
+    ) {
+        if weights.is_empty() {
+            self.report(file, path, "no branches to choose from");
+            return;
+        }
+        for (i, &weight) in weights.iter().enumerate() {
+            if !weight.is_finite() || weight < 0.0 {
+                self.report(file, weight_path(i), format!("invalid weight: {}", weight));
+            }
+        }
+        if weights.iter().sum::<f64>() <= 0.0 {
+            self.report(file, path, "total weight must be positive");
+        }
+    }
+
+    fn check_skill_rarities(&mut self, file: &Path, path: &str, skills: &[SkillRarityWeight]) {
+        let weights = skills.iter().map(|s| s.weight).collect::<Vec<_>>();
+        self.check_weights(file, path, &weights, |i| format!("{}[{}].weight", path, i));
+        for (i, skill) in skills.iter().enumerate() {
+            if !self.skill_rarities.contains(&skill.rarity) {
+                self.report(
+                    file,
+                    format!("{}[{}].rarity", path, i),
+                    format!("no skill has rarity {}", skill.rarity),
+                );
+            }
+        }
+    }
+
+    fn check_effects(&mut self, file: &Path, path: &str, effects: &[Effect]) {
+        for (i, effect) in effects.iter().enumerate() {
+            let path = format!("{}[{}]", path, i);
+            match *effect {
+                Effect::Damage { multiplier } => {
+                    self.check_positive(file, format!("{}.Damage.multiplier", path), multiplier);
+                }
+                Effect::MultiHit { multiplier, hits } => {
+                    self.check_positive(file, format!("{}.MultiHit.multiplier", path), multiplier);
+                    self.check_positive(file, format!("{}.MultiHit.hits", path), hits as f64);
+                }
+                Effect::Heal { amount } => {
+                    self.check_positive(file, format!("{}.Heal.amount", path), amount as f64);
+                }
+                Effect::HealRatio { ratio } => {
+                    self.check_positive(file, format!("{}.HealRatio.ratio", path), ratio);
+                }
+                Effect::GainTp { amount } => {
+                    self.check_positive(file, format!("{}.GainTp.amount", path), amount as f64);
+                }
+                Effect::Buff { turns, .. } => {
+                    self.check_positive(file, format!("{}.Buff.turns", path), turns as f64);
+                }
+                Effect::Debuff { turns, .. } => {
+                    self.check_positive(file, format!("{}.Debuff.turns", path), turns as f64);
+                }
+                Effect::Cure { .. } | Effect::SetState { .. } | Effect::AddState { .. } => (),
+            }
+        }
+    }
+
+    fn check_positive(&mut self, file: &Path, path: String, value: f64) {
+        if !value.is_finite() || value <= 0.0 {
+            self.report(file, path, format!("must be positive: {}", value));
+        }
+    }
+
+    fn check_opening(&mut self, file: &Path, path: &str, data: &OpeningData) {
+        match data {
+            OpeningData::Random { data } => {
+                let path = child_path(path, "Random.data");
+                let weights = data.iter().map(|(w, _)| *w).collect::<Vec<_>>();
+                self.check_weights(file, &path, &weights, |i| format!("{}[{}][0]", path, i));
+                for (i, (_, data)) in data.iter().enumerate() {
+                    self.check_opening(file, &format!("{}[{}][1]", path, i), data);
+                }
+            }
+            OpeningData::Sequence { data } => {
+                for (i, data) in data.iter().enumerate() {
+                    let path = child_path(path, &format!("Sequence.data[{}]", i));
+                    self.check_opening(file, &path, data);
+                }
+            }
+            OpeningData::Data { data } => {
+                self.check_text(file, child_path(path, "Data.data"), data);
+            }
+        }
+    }
+
+    fn check_player(&mut self, file: &Path, player_data: &PlayerData) {
+        self.check_image(file, "image".into(), &player_data.image);
+        self.check_image(file, "shadow_image".into(), &player_data.shadow_image);
+        self.check_text(
+            file,
+            "opening_legendary_name".into(),
+            &player_data.opening_legendary_name,
+        );
+        self.check_text(
+            file,
+            "opening_introduction_text".into(),
+            &player_data.opening_introduction_text,
+        );
+        for (i, prologue) in player_data.prologue.iter().enumerate() {
+            for (j, message) in prologue.messages.iter().enumerate() {
+                self.check_text(file, format!("prologue[{}].messages[{}]", i, j), &message.0);
+            }
+        }
+
+        let mut skill_ids = HashSet::new();
+        let mut rarity_weights = HashMap::new();
+        for (i, skill) in player_data.skills.iter().enumerate() {
+            *rarity_weights.entry(skill.rarity).or_insert(0.0) += skill.rarity_weight;
+            if !skill_ids.insert(skill.id) {
+                self.report(
+                    file,
+                    format!("skills[{}].id", i),
+                    format!("duplicate skill id: {}", skill.id.0),
+                );
+            }
+            if !skill.rarity_weight.is_finite() || skill.rarity_weight < 0.0 {
+                self.report(
+                    file,
+                    format!("skills[{}].rarity_weight", i),
+                    format!("invalid weight: {}", skill.rarity_weight),
+                );
+            }
+            self.check_effects(file, &format!("skills[{}].effects", i), &skill.effects);
+            self.check_text(file, format!("skills[{}].skill_name", i), &skill.skill_name);
+            self.check_text(
+                file,
+                format!("skills[{}].skill_name_with_level", i),
+                &skill.skill_name_with_level,
+            );
+            self.check_text(
+                file,
+                format!("skills[{}].skill_description", i),
+                &skill.skill_description,
+            );
+            self.check_text(
+                file,
+                format!("skills[{}].get_skill_confirm_message", i),
+                &skill.get_skill_confirm_message,
+            );
+        }
+        let mut rarity_weights = rarity_weights.into_iter().collect::<Vec<_>>();
+        rarity_weights.sort_by_key(|&(rarity, _)| rarity);
+        for (rarity, weight) in rarity_weights {
+            if weight <= 0.0 {
+                self.report(
+                    file,
+                    "skills",
+                    format!("total rarity_weight of rarity {} must be positive", rarity),
+                );
+            }
+        }
+    }
+
+    fn check_item(&mut self, file: &Path, index: usize, item_data: &ItemData) {
+        self.check_effects(file, &format!("[{}].effects", index), &item_data.effects);
+        self.check_text(file, format!("[{}].item_name", index), &item_data.item_name);
+        self.check_text(
+            file,
+            format!("[{}].item_name_with_count", index),
+            &item_data.item_name_with_count,
+        );
+        self.check_text(
+            file,
+            format!("[{}].item_description", index),
+            &item_data.item_description,
+        );
+    }
+
+    fn check_action_pattern(&mut self, file: &Path, path: &str, pattern: &EnemyActionPattern) {
+        match pattern {
+            EnemyActionPattern::Action(action) => {
+                self.check_effects(file, &format!("{}.Action.effects", path), &action.effects);
+                self.check_text(file, format!("{}.Action.message", path), &action.message);
+            }
+            EnemyActionPattern::Weighted { branches } => {
+                let path = format!("{}.Weighted.branches", path);
+                let weights = branches.iter().map(|b| b.weight).collect::<Vec<_>>();
+                self.check_weights(file, &path, &weights, |i| format!("{}[{}].weight", path, i));
+                for (i, branch) in branches.iter().enumerate() {
+                    self.check_action_pattern(
+                        file,
+                        &format!("{}[{}].pattern", path, i),
+                        &branch.pattern,
+                    );
+                }
+            }
+            EnemyActionPattern::Conditional {
+                branches,
+                otherwise,
+            } => {
+                for (i, branch) in branches.iter().enumerate() {
+                    self.check_action_pattern(
+                        file,
+                        &format!("{}.Conditional.branches[{}].pattern", path, i),
+                        &branch.pattern,
+                    );
+                }
+                self.check_action_pattern(
+                    file,
+                    &format!("{}.Conditional.otherwise", path),
+                    otherwise,
+                );
+            }
+            EnemyActionPattern::Rotation { patterns, .. } => {
+                if patterns.is_empty() {
+                    self.report(
+                        file,
+                        format!("{}.Rotation.patterns", path),
+                        "no patterns to rotate",
+                    );
+                }
+                for (i, pattern) in patterns.iter().enumerate() {
+                    self.check_action_pattern(
+                        file,
+                        &format!("{}.Rotation.patterns[{}]", path, i),
+                        pattern,
+                    );
+                }
+            }
+        }
+    }
+
+    fn check_enemy(&mut self, file: &Path, enemy_data: &EnemyData) {
+        self.check_text(file, "enemy_name".into(), &enemy_data.enemy_name);
+        self.check_text(file, "description".into(), &enemy_data.description);
+        self.check_image(file, "image".into(), &enemy_data.image);
+        self.check_image(file, "shadow_image".into(), &enemy_data.shadow_image);
+        self.check_sfx(file, "bark_sfx".into(), &enemy_data.bark_sfx);
+        if let Some(bgm) = &enemy_data.bgm {
+            self.check_bgm(file, "bgm".into(), bgm);
+        }
+        self.check_action_pattern(file, "action_pattern", &enemy_data.action_pattern);
+        for (i, phase) in enemy_data.phases.iter().flatten().enumerate() {
+            self.check_text(file, format!("phases[{}].message", i), &phase.message);
+            self.check_action_pattern(
+                file,
+                &format!("phases[{}].action_pattern", i),
+                &phase.action_pattern,
+            );
+        }
+
+        if let Some(drops) = &enemy_data.drops {
+            if let Some(items) = &drops.items {
+                let weights = items.iter().map(|d| d.weight).collect::<Vec<_>>();
+                self.check_weights(file, "drops.items", &weights, |i| {
+                    format!("drops.items[{}].weight", i)
+                });
+                for (i, item) in items.iter().enumerate() {
+                    if let Some(item_id) = item.item_id {
+                        self.check_item_id(file, format!("drops.items[{}].item_id", i), item_id);
+                    }
+                }
+            }
+            if let Some(skills) = &drops.skills {
+                self.check_skill_rarities(file, "drops.skills.skills", &skills.skills);
+            }
+        }
+    }
+
+    fn check_level_id(&mut self, file: &Path, path: String, id: &str) {
+        if !self.level_ids.contains(id) {
+            self.report(file, path, format!("level id not found: {}", id));
+        }
+    }
+
+    fn check_costs(&mut self, file: &Path, path: &str, costs: &[Cost]) {
+        for (i, cost) in costs.iter().enumerate() {
+            if let Cost::Item { item_id, .. } = cost {
+                self.check_item_id(file, format!("{}[{}].Item.item_id", path, i), *item_id);
+            }
+        }
+    }
+
+    fn check_condition(&mut self, file: &Path, path: &str, condition: &LevelCondition) {
+        match condition {
+            LevelCondition::HasItem { item_id, .. } => {
+                self.check_item_id(file, format!("{}.HasItem.item_id", path), *item_id);
+            }
+            LevelCondition::HasSkill { skill_id } => {
+                if !self.skill_ids.contains(skill_id) {
+                    self.report(
+                        file,
+                        format!("{}.HasSkill.skill_id", path),
+                        format!("skill id not found: {}", skill_id.0),
+                    );
+                }
+            }
+            LevelCondition::HasSkillType { skill_type } => {
+                if !self.skill_types.contains(skill_type) {
+                    self.report(
+                        file,
+                        format!("{}.HasSkillType.skill_type", path),
+                        format!("skill type not found: {}", skill_type),
+                    );
+                }
+            }
+            LevelCondition::Not { condition } => {
+                self.check_condition(file, &format!("{}.Not.condition", path), condition);
+            }
+            LevelCondition::All { conditions } => {
+                for (i, condition) in conditions.iter().enumerate() {
+                    self.check_condition(
+                        file,
+                        &format!("{}.All.conditions[{}]", path, i),
+                        condition,
+                    );
+                }
+            }
+            LevelCondition::Any { conditions } => {
+                for (i, condition) in conditions.iter().enumerate() {
+                    self.check_condition(
+                        file,
+                        &format!("{}.Any.conditions[{}]", path, i),
+                        condition,
+                    );
+                }
+            }
+            LevelCondition::HpBelow { .. }
+            | LevelCondition::FlagSet { .. }
+            | LevelCondition::CounterAtLeast { .. }
+            | LevelCondition::CounterBelow { .. }
+            | LevelCondition::Time { .. }
+            | LevelCondition::DepthAtLeast { .. }
+            | LevelCondition::DepthBelow { .. } => (),
+        }
+    }
+
+    fn check_level_item(&mut self, file: &Path, path: &str, item: &LevelItem) {
+        match item {
+            LevelItem::Sequence { items } => {
+                for (i, item) in items.iter().enumerate() {
+                    self.check_level_item(file, &format!("{}.Sequence.items[{}]", path, i), item);
+                }
+            }
+            LevelItem::Random { branches } => {
+                let path = format!("{}.Random.branches", path);
+                let weights = branches.iter().map(|b| b.weight).collect::<Vec<_>>();
+                self.check_weights(file, &path, &weights, |i| format!("{}[{}].weight", path, i));
+                for (i, branch) in branches.iter().enumerate() {
+                    if let Some(condition) = &branch.condition {
+                        self.check_condition(
+                            file,
+                            &format!("{}[{}].condition", path, i),
+                            condition,
+                        );
+                    }
+                    self.check_level_item(file, &format!("{}[{}].item", path, i), &branch.item);
+                }
+            }
+            LevelItem::Message { text, args, .. } => {
+                self.check_text(file, format!("{}.Message.text", path), text);
+                for (i, arg) in args.iter().flatten().enumerate() {
+                    if let MessageArg::Flag { set, unset, .. } = arg {
+                        let path = format!("{}.Message.args[{}].Flag", path, i);
+                        self.check_text(file, format!("{}.set", path), set);
+                        self.check_text(file, format!("{}.unset", path), unset);
+                    }
+                }
+            }
+            LevelItem::Choice { text, branches } => {
+                self.check_text(file, format!("{}.Choice.text", path), text);
+                if branches.is_empty() {
+                    self.report(
+                        file,
+                        format!("{}.Choice.branches", path),
+                        "no branches to choose from",
+                    );
+                }
+                for (i, branch) in branches.iter().enumerate() {
+                    let path = format!("{}.Choice.branches[{}]", path, i);
+                    self.check_text(file, format!("{}.text", path), &branch.text);
+                    if let Some(condition) = &branch.condition {
+                        self.check_condition(file, &format!("{}.condition", path), condition);
+                    }
+                    if let Some(costs) = &branch.costs {
+                        self.check_costs(file, &format!("{}.costs", path), costs);
+                    }
+                    self.check_level_item(file, &format!("{}.item", path), &branch.item);
+                }
+            }
+            LevelItem::If {
+                condition,
+                then,
+                otherwise,
+            } => {
+                self.check_condition(file, &format!("{}.If.condition", path), condition);
+                self.check_level_item(file, &format!("{}.If.then", path), then);
+                if let Some(otherwise) = otherwise {
+                    self.check_level_item(file, &format!("{}.If.else", path), otherwise);
+                }
+            }
+            LevelItem::PlayBGM { bgm } => {
+                self.check_bgm(file, format!("{}.PlayBGM.bgm", path), bgm);
+            }
+            LevelItem::ResumeOrPlayBGM { bgm } => {
+                self.check_bgm(file, format!("{}.ResumeOrPlayBGM.bgm", path), bgm);
+            }
+            LevelItem::Battle {
+                id,
+                bgm,
+                companions,
+                escape_penalty,
+                ..
+            } => {
+                self.check_enemy_id(file, format!("{}.Battle.id", path), *id);
+                self.check_bgm(file, format!("{}.Battle.bgm", path), bgm);
+                let enemy_count = 1 + companions.as_ref().map_or(0, |c| c.len());
+                if enemy_count > MAX_ENEMIES {
+                    self.report(
+                        file,
+                        format!("{}.Battle.companions", path),
+                        format!(
+                            "too many enemies in one battle: {} (max {})",
+                            enemy_count, MAX_ENEMIES
+                        ),
+                    );
+                }
+                for (i, &companion) in companions.iter().flatten().enumerate() {
+                    self.check_enemy_id(
+                        file,
+                        format!("{}.Battle.companions[{}]", path, i),
+                        companion,
+                    );
+                }
+                if let Some(escape_penalty) = escape_penalty {
+                    self.check_level_item(
+                        file,
+                        &format!("{}.Battle.escape_penalty", path),
+                        escape_penalty,
+                    );
+                }
+            }
+            LevelItem::GetSkill { skills, .. } => {
+                self.check_skill_rarities(file, &format!("{}.GetSkill.skills", path), skills);
+            }
+            LevelItem::AddItem { item_id, .. } => {
+                self.check_item_id(file, format!("{}.AddItem.item_id", path), *item_id);
+            }
+            LevelItem::RemoveItem { item_id, .. } => {
+                self.check_item_id(file, format!("{}.RemoveItem.item_id", path), *item_id);
+            }
+            LevelItem::Trade {
+                give,
+                receive,
+                then,
+                otherwise,
+            } => {
+                self.check_costs(file, &format!("{}.Trade.give", path), give);
+                for (i, item) in receive.iter().enumerate() {
+                    self.check_item_id(
+                        file,
+                        format!("{}.Trade.receive[{}].item_id", path, i),
+                        item.item_id,
+                    );
+                }
+                if let Some(then) = then {
+                    self.check_level_item(file, &format!("{}.Trade.then", path), then);
+                }
+                if let Some(otherwise) = otherwise {
+                    self.check_level_item(file, &format!("{}.Trade.else", path), otherwise);
+                }
+            }
+            LevelItem::JumpTo { level } => {
+                self.check_level_id(file, format!("{}.JumpTo.level", path), level);
+            }
+            LevelItem::Skip { count } => {
+                if *count >= self.remaining_levels {
+                    self.report(
+                        file,
+                        format!("{}.Skip.count", path),
+                        format!("skip of {} levels goes past the last level", count),
+                    );
+                }
+            }
+            LevelItem::StopBGM
+            | LevelItem::ChangeToAfternoon
+            | LevelItem::ChangeToNight
+            | LevelItem::WaitOpenSkillItemList
+            | LevelItem::Heal { .. }
+            | LevelItem::Damage { .. }
+            | LevelItem::RestoreTp { .. }
+            | LevelItem::SetFlag { .. }
+            | LevelItem::ClearFlag { .. }
+            | LevelItem::AddCounter { .. } => (),
+        }
+    }
+
+    fn check_ending(&mut self, file: &Path, ending_data: &EndingData) {
+        self.check_bgm(file, "bgm".into(), &ending_data.bgm);
+        for (i, ending) in ending_data.endings.iter().enumerate() {
+            if !self.player_indices.contains(&ending.player_index) {
+                self.report(
+                    file,
+                    format!("endings[{}].player_index", i),
+                    format!("player index not found: {}", ending.player_index),
+                );
+            }
+            for (j, message) in ending.messages.iter().enumerate() {
+                self.check_text(file, format!("endings[{}].messages[{}]", i, j), message);
+            }
+        }
+
+        let mut player_indices = self.player_indices.iter().copied().collect::<Vec<_>>();
+        player_indices.sort_unstable();
+        for player_index in player_indices {
+            if !ending_data
+                .endings
+                .iter()
+                .any(|e| e.player_index == player_index)
+            {
+                self.report(
+                    file,
+                    "endings",
+                    format!("no ending for player index {}", player_index),
+                );
+            }
+        }
+    }
+
+    fn check_credits(&mut self, file: &Path, credits_data: &CreditsData) {
+        if let Some(bgm) = &credits_data.bgm {
+            self.check_bgm(file, "bgm".into(), bgm);
+        }
+        if credits_data.scroll_speed.is_nan() || credits_data.scroll_speed <= 0.0 {
+            self.report(
+                file,
+                "scroll_speed",
+                format!(
+                    "scroll speed must be positive: {}",
+                    credits_data.scroll_speed
+                ),
+            );
+        }
+        for (i, line) in credits_data.lines.iter().enumerate() {
+            match line {
+                CreditsLine::Title { text } => {
+                    self.check_text(file, format!("lines[{}].Title.text", i), text)
+                }
+                CreditsLine::Heading { text } => {
+                    self.check_text(file, format!("lines[{}].Heading.text", i), text)
+                }
+                CreditsLine::Name { text } => {
+                    self.check_text(file, format!("lines[{}].Name.text", i), text)
+                }
+                CreditsLine::Space => (),
+            }
+        }
+    }
+}
+
+/// リソースとi18nのディレクトリを読み込み、見つかった問題を最初の一つで止めずにすべて返す
+pub fn validate_dir(
+    resources_dir: impl AsRef<Path>,
+    i18n_dir: impl AsRef<Path>,
+) -> Vec<ValidationProblem> {
+    let mut validator = Validator {
+        source: DirSource(resources_dir.as_ref().to_path_buf()),
+        texts: None,
+        bgm: None,
+        item_ids: HashSet::new(),
+        enemy_ids: HashSet::new(),
+        player_indices: HashSet::new(),
+        level_ids: HashSet::new(),
+        skill_ids: HashSet::new(),
+        skill_types: HashSet::new(),
+        skill_rarities: HashSet::new(),
+        remaining_levels: 0,
+        problems: vec![],
+    };
+    validator.load_i18n(i18n_dir.as_ref());
+    validator.load_bgm();
+
+    // 参照先のidを先に集めてから、各データの参照を照合する
+    let item_file = Path::new("/game_data/item-data.yml");
+    let item_data: Vec<ItemData> = validator.read_yaml(item_file).unwrap_or_default();
+    for (i, item) in item_data.iter().enumerate() {
+        if !validator.item_ids.insert(item.id) {
+            validator.report(
+                item_file,
+                format!("[{}].id", i),
+                format!("duplicate item id: {}", item.id.0),
+            );
+        }
+    }
+
+    let player_data: Vec<(PathBuf, PlayerData)> =
+        validator.read_yaml_dir("/game_data/player-data/");
+    for (file, player) in player_data.iter() {
+        if !validator.player_indices.insert(player.index) {
+            validator.report(
+                file,
+                "index",
+                format!("duplicate player index: {}", player.index),
+            );
+        }
+        for skill in player.skills.iter() {
+            validator.skill_ids.insert(skill.id);
+            validator.skill_types.insert(skill.skill_type);
+            validator.skill_rarities.insert(skill.rarity);
+        }
+    }
+
+    let enemy_data: Vec<(PathBuf, EnemyData)> = validator.read_yaml_dir("/game_data/enemy-data/");
+    for (file, enemy) in enemy_data.iter() {
+        if !validator.enemy_ids.insert(enemy.id) {
+            validator.report(file, "id", format!("duplicate enemy id: {}", enemy.id.0));
+        }
+    }
+
+    let level_data: Vec<(PathBuf, LevelData)> = validator.read_yaml_dir("/game_data/level-data/");
+    let mut level_indices = HashSet::new();
+    for (file, level) in level_data.iter() {
+        if !level_indices.insert(level.index) {
+            validator.report(
+                file,
+                "index",
+                format!("duplicate level index: {}", level.index),
+            );
+        }
+        if let Some(id) = &level.id {
+            if !validator.level_ids.insert(id.clone()) {
+                validator.report(file, "id", format!("duplicate level id: {}", id));
+            }
+        }
+    }
+
+    let opening_file = Path::new("/game_data/opening-data.yml");
+    if let Some(opening_data) = validator.read_yaml(opening_file) {
+        validator.check_opening(opening_file, "", &opening_data);
+    }
+    for (i, item) in item_data.iter().enumerate() {
+        validator.check_item(item_file, i, item);
+    }
+    for (file, player) in player_data.iter() {
+        validator.check_player(file, player);
+    }
+    for (file, enemy) in enemy_data.iter() {
+        validator.check_enemy(file, enemy);
+    }
+    // Skipはゲーム中と同じくindex順に並べたときの位置から数える
+    let mut level_order = level_indices.into_iter().collect::<Vec<_>>();
+    level_order.sort_unstable();
+    for (file, level) in level_data.iter() {
+        if let Some(next) = &level.next {
+            validator.check_level_id(file, "next".into(), next);
+        }
+        let position = level_order.binary_search(&level.index).unwrap_or_default();
+        validator.remaining_levels = level_order.len() - position - 1;
+        validator.check_level_item(file, "item", &level.item);
+    }
+    let ending_file = Path::new("/game_data/ending-data.yml");
+    if let Some(ending_data) = validator.read_yaml(ending_file) {
+        validator.check_ending(ending_file, &ending_data);
+    }
+    let credits_file = Path::new("/game_data/credits-data.yml");
+    if let Some(credits_data) = validator.read_yaml(credits_file) {
+        validator.check_credits(credits_file, &credits_data);
+    }
+
+    validator.problems
+}
@@ -0,0 +1,26 @@
+use animation_engine::*;
+
+mod assets_load;
+mod game;
+pub mod game_data;
+mod init_logger;
+mod input;
+mod localization;
+mod save_data;
+
+pub use game::simulator;
+pub use game::PlayerState;
+pub use localization::load_original_texts;
+
+pub fn run() -> anyhow::Result<()> {
+    init_logger::init_logger();
+
+    let mut engine = AnimationEngine::new("Towards The End of Greenish-X")?;
+    assets_load::load(&mut engine)?;
+    localization::set_localize(&mut engine);
+
+    let global_data = game::GlobalData::load(&mut engine)?;
+    let game = game::game(global_data);
+
+    engine.run_with_async_func(game)
+}
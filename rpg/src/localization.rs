@@ -5,13 +5,21 @@ use std::collections::HashMap;
 use std::env;
 use std::fs;
 use std::io;
+use std::path::Path;
 use std::thread_local;
 
 #[derive(Deserialize)]
-struct LocalizeInfo {
+pub(crate) struct LocalizeInfo {
     #[serde(alias = "font-file")]
-    font_name: String,
-    texts: HashMap<String, String>,
+    pub(crate) font_name: String,
+    pub(crate) texts: HashMap<String, String>,
+}
+impl LocalizeInfo {
+    pub(crate) fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let file = fs::File::open(path)?;
+        let reader = io::BufReader::new(file);
+        Ok(serde_yaml::from_reader(reader)?)
+    }
 }
 
 pub struct LocalizeTexts {
@@ -26,10 +34,7 @@ impl LocalizeTexts {
         let exe_dir = exe_path.parent().unwrap();
         let i18n_dir = exe_dir.join("i18n");
 
-        let file = fs::File::open(&i18n_dir.join("jp-original.yml"))?;
-        let reader = io::BufReader::new(file);
-        let jp_info: LocalizeInfo = serde_yaml::from_reader(reader)?;
-        infos.push(jp_info);
+        infos.push(LocalizeInfo::load(i18n_dir.join("jp-original.yml"))?);
 
         for entry in fs::read_dir(&i18n_dir)? {
             let entry = entry?;
@@ -39,10 +44,7 @@ impl LocalizeTexts {
                     continue;
                 }
             }
-            let file = fs::File::open(path)?;
-            let reader = io::BufReader::new(file);
-            let info: LocalizeInfo = serde_yaml::from_reader(reader)?;
-            infos.push(info);
+            infos.push(LocalizeInfo::load(path)?);
         }
 
         Ok(Self {
@@ -58,12 +60,15 @@ impl LocalizeTexts {
     fn get(&self, key: &str) -> LocalizeText {
         let info = &self.infos[self.current_index];
         let font_name = info.font_name.to_owned();
-        let text = info
-            .texts
+        LocalizeText::new(font_name, self.get_text(key))
+    }
+
+    fn get_text(&self, key: &str) -> String {
+        let info = &self.infos[self.current_index];
+        info.texts
             .get(key)
             .expect(&format!("no text data in localization file: {}", key))
-            .to_owned();
-        LocalizeText::new(font_name, text)
+            .to_owned()
     }
 
     fn change_language(&mut self, cx: &AnimationEngineContext, index: usize) {
@@ -103,6 +108,20 @@ pub fn set_localize(engine: &mut AnimationEngine) {
     LOCALIZE_TEXTS.with(|texts| texts.borrow().init(engine.get_context()))
 }
 
+pub fn get_text(key: &str) -> String {
+    LOCALIZE_TEXTS.with(|texts| texts.borrow().get_text(key))
+}
+
+pub fn get_formatted_text(key: &str, args: &[&str]) -> String {
+    args.iter()
+        .fold(get_text(key), |text, arg| text.replacen("{}", arg, 1))
+}
+
+/// エンジンを使わずに原文(jp-original.yml)のテキストを読み込む
+pub fn load_original_texts(i18n_dir: impl AsRef<Path>) -> anyhow::Result<HashMap<String, String>> {
+    Ok(LocalizeInfo::load(i18n_dir.as_ref().join("jp-original.yml"))?.texts)
+}
+
 pub fn len() -> usize {
     LOCALIZE_TEXTS.with(|texts| texts.borrow().len())
 }
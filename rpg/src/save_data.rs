@@ -1,29 +1,150 @@
 use animation_engine::AnimationEngineContext;
 use log::{info, trace};
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::env;
 use std::fs;
 use std::io;
-use std::io::Seek;
+use std::io::{Read, Seek, Write};
 
+use crate::game_data::*;
 use crate::localization;
 
 const MAX_VOLUME: u8 = 15;
+const VERSION: u8 = 1;
+/// 保存しておく探索の記録の数。古いものから消え、集計はRunTotalsに残る
+const MAX_RUN_HISTORY: usize = 50;
 
+/// version 0はフィールド名なしの配列として保存していたため、後からフィールドを足せない
+#[derive(Deserialize)]
+struct SaveDataContentV0 {
+    _version: u8,
+    bgm_volume: u8,
+    sfx_volume: u8,
+    language: usize,
+}
+
+/// 探索の途中の状態。最大HPなどはプレイヤーのデータから復元する
+#[derive(Deserialize, Serialize, Clone)]
+pub struct RunState {
+    pub level_index: usize,
+    pub depth: u32,
+    pub hp: i32,
+    pub tp: i32,
+    pub gold: u32,
+    pub items: Vec<(ItemId, u32)>,
+    pub skills: Vec<SkillId>,
+    pub flags: Vec<String>,
+    pub counters: Vec<(String, i32)>,
+    /// 永続するフラグとカウンタの名前。保存する前の中断データではNone
+    pub persistent_flags: Option<Vec<String>>,
+    pub persistent_counters: Option<Vec<String>>,
+    pub explore_bgm: String,
+    pub time: BattleTime,
+    pub battle_count: Option<u32>,
+    pub turn_count: Option<u32>,
+}
+
+/// 中断した探索。レベルを抜けるたびに次のレベルに入る時点の状態で上書きする
+#[derive(Deserialize, Serialize, Clone)]
+pub struct SuspendData {
+    pub player_index: usize,
+    /// 探索を始めたときのシード。シードを保存する前の中断データではNone
+    pub run_seed: Option<u64>,
+    /// 再開時にこの値で乱数を初期化する
+    pub rng_seed: u64,
+    pub state: RunState,
+    /// 力尽きたときに戻るチェックポイント
+    pub checkpoint: Option<RunState>,
+}
+
+#[derive(Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub enum RunResult {
+    Clear,
+    /// enemy_nameは倒された敵の名前のキー
+    KilledBy {
+        enemy_name: String,
+    },
+    Exhausted,
+}
+
+/// 終わった探索の記録
+#[derive(Deserialize, Serialize, Clone)]
+pub struct RunRecord {
+    pub player_index: usize,
+    pub seed: Option<u64>,
+    pub depth: u32,
+    pub max_depth: u32,
+    pub result: RunResult,
+    pub skills: Vec<SkillId>,
+    pub battle_count: u32,
+    pub turn_count: u32,
+    /// UNIX時間(秒)
+    pub timestamp: i64,
+}
+
+/// 履歴から消えた記録も含めた探索の集計
+#[derive(Deserialize, Serialize, Clone, Default)]
+pub struct RunTotals {
+    pub runs: u32,
+    pub clears: u32,
+    pub battles: u32,
+    pub turns: u32,
+    /// キャラクター(player_index)ごとの最高到達深さ
+    pub best_depth: HashMap<usize, u32>,
+}
+impl RunTotals {
+    fn add(&mut self, record: &RunRecord) {
+        self.runs = self.runs.saturating_add(1);
+        if record.result == RunResult::Clear {
+            self.clears = self.clears.saturating_add(1);
+        }
+        self.battles = self.battles.saturating_add(record.battle_count);
+        self.turns = self.turns.saturating_add(record.turn_count);
+        let best_depth = self.best_depth.entry(record.player_index).or_insert(0);
+        *best_depth = (*best_depth).max(record.depth);
+    }
+}
+
+/// version 1以降はフィールド名付きで保存するので、追加するフィールドはOptionにする
 #[derive(Deserialize, Serialize)]
 struct SaveDataContent {
     version: u8,
     bgm_volume: u8,
     sfx_volume: u8,
     language: usize,
+    flags: Option<HashSet<String>>,
+    counters: Option<HashMap<String, i32>>,
+    suspend: Option<SuspendData>,
+    run_history: Option<Vec<RunRecord>>,
+    run_totals: Option<RunTotals>,
+    encountered_enemies: Option<HashSet<EnemyId>>,
+    defeated_enemies: Option<HashMap<EnemyId, u32>>,
 }
 impl SaveDataContent {
     fn new() -> Self {
         Self {
-            version: 0,
+            version: VERSION,
             bgm_volume: 7,
             sfx_volume: 7,
             language: 0,
+            flags: None,
+            counters: None,
+            suspend: None,
+            run_history: None,
+            run_totals: None,
+            encountered_enemies: None,
+            defeated_enemies: None,
+        }
+    }
+}
+impl From<SaveDataContentV0> for SaveDataContent {
+    fn from(data: SaveDataContentV0) -> Self {
+        Self {
+            bgm_volume: data.bgm_volume,
+            sfx_volume: data.sfx_volume,
+            language: data.language,
+            ..Self::new()
         }
     }
 }
@@ -43,10 +164,17 @@ impl SaveData {
             .write(true)
             .create(true)
             .open(save_data_path)?;
-        let reader = io::BufReader::new(&file);
-        if let Ok(data) = rmp_serde::from_read::<_, SaveDataContent>(reader) {
+        let mut bytes = vec![];
+        io::BufReader::new(&file).read_to_end(&mut bytes)?;
+        if let Ok(data) = rmp_serde::from_slice::<SaveDataContent>(&bytes) {
             trace!("Load save_data file.");
             Ok(Self { file, data })
+        } else if let Ok(data) = rmp_serde::from_slice::<SaveDataContentV0>(&bytes) {
+            trace!("Migrate save_data file from version 0.");
+            Ok(Self {
+                file,
+                data: data.into(),
+            })
         } else {
             trace!("Create save_data file.");
             Ok(Self {
@@ -60,7 +188,12 @@ impl SaveData {
         trace!("Save save_data.");
         self.file.seek(io::SeekFrom::Start(0)).unwrap();
         let mut writer = io::BufWriter::new(&mut self.file);
-        rmp_serde::encode::write(&mut writer, &self.data)?;
+        rmp_serde::encode::write_named(&mut writer, &self.data)?;
+        writer.flush()?;
+        drop(writer);
+        // 中断データを消したときなど前回より短くなった場合に、古いデータが後ろに残らないようにする
+        let len = self.file.stream_position()?;
+        self.file.set_len(len)?;
         Ok(())
     }
 
@@ -110,6 +243,120 @@ impl SaveData {
         self.save()
     }
 
+    pub fn flags(&self) -> impl Iterator<Item = &String> {
+        self.data.flags.iter().flatten()
+    }
+
+    pub fn set_flag(&mut self, flag: &str) -> anyhow::Result<()> {
+        trace!("Set and save flag: {}", flag);
+        self.data
+            .flags
+            .get_or_insert_with(HashSet::new)
+            .insert(flag.to_string());
+        self.save()
+    }
+
+    pub fn clear_flag(&mut self, flag: &str) -> anyhow::Result<()> {
+        trace!("Clear and save flag: {}", flag);
+        if let Some(flags) = &mut self.data.flags {
+            flags.remove(flag);
+        }
+        self.save()
+    }
+
+    pub fn counters(&self) -> impl Iterator<Item = (&String, &i32)> {
+        self.data.counters.iter().flatten()
+    }
+
+    pub fn add_counter(&mut self, counter: &str, amount: i32) -> anyhow::Result<()> {
+        trace!("Add and save counter: {}, {}", counter, amount);
+        *self
+            .data
+            .counters
+            .get_or_insert_with(HashMap::new)
+            .entry(counter.to_string())
+            .or_default() += amount;
+        self.save()
+    }
+
+    pub fn suspend_data(&self) -> Option<&SuspendData> {
+        self.data.suspend.as_ref()
+    }
+
+    pub fn set_suspend_data(&mut self, suspend: SuspendData) -> anyhow::Result<()> {
+        trace!(
+            "Save suspend data. level index: {}",
+            suspend.state.level_index
+        );
+        self.data.suspend = Some(suspend);
+        self.save()
+    }
+
+    pub fn clear_suspend_data(&mut self) -> anyhow::Result<()> {
+        if self.data.suspend.take().is_some() {
+            trace!("Clear suspend data.");
+            self.save()
+        } else {
+            Ok(())
+        }
+    }
+
+    /// 古いものから順に並んでいる
+    pub fn run_history(&self) -> &[RunRecord] {
+        self.data.run_history.as_deref().unwrap_or(&[])
+    }
+
+    pub fn run_totals(&self) -> RunTotals {
+        self.data.run_totals.clone().unwrap_or_default()
+    }
+
+    pub fn add_run_record(&mut self, record: RunRecord) -> anyhow::Result<()> {
+        trace!("Add and save run record. depth: {}", record.depth);
+        self.data
+            .run_totals
+            .get_or_insert_with(RunTotals::default)
+            .add(&record);
+        let history = self.data.run_history.get_or_insert_with(Vec::new);
+        history.push(record);
+        if history.len() > MAX_RUN_HISTORY {
+            history.drain(..history.len() - MAX_RUN_HISTORY);
+        }
+        self.save()
+    }
+
+    pub fn is_enemy_encountered(&self, enemy_id: EnemyId) -> bool {
+        self.data
+            .encountered_enemies
+            .iter()
+            .any(|enemies| enemies.contains(&enemy_id))
+    }
+
+    pub fn record_encounter(&mut self, enemy_ids: &[EnemyId]) -> anyhow::Result<()> {
+        trace!("Record and save encounter: {:?}", enemy_ids);
+        self.data
+            .encountered_enemies
+            .get_or_insert_with(HashSet::new)
+            .extend(enemy_ids.iter().copied());
+        self.save()
+    }
+
+    pub fn defeated_count(&self, enemy_id: EnemyId) -> u32 {
+        self.data
+            .defeated_enemies
+            .as_ref()
+            .and_then(|enemies| enemies.get(&enemy_id).copied())
+            .unwrap_or(0)
+    }
+
+    pub fn record_defeat(&mut self, enemy_ids: &[EnemyId]) -> anyhow::Result<()> {
+        trace!("Record and save defeat: {:?}", enemy_ids);
+        let defeated_enemies = self.data.defeated_enemies.get_or_insert_with(HashMap::new);
+        for &enemy_id in enemy_ids.iter() {
+            *defeated_enemies.entry(enemy_id).or_default() += 1;
+        }
+        self.save()
+    }
+
     pub fn apply(&self, cx: &AnimationEngineContext) {
         cx.set_bgm_volume(self.bgm_volume() as f32 * 0.1);
         cx.set_sfx_volume(self.sfx_volume() as f32 * 0.1);